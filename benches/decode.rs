@@ -0,0 +1,106 @@
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use qoi_decoder::{DecodeContext, DecodeOptions, EncodeOptions, ImageData};
+
+const FIXTURES: [(&str, &[u8]); 3] = [
+    ("photo", include_bytes!("../qoi_test_images/kodim23.qoi")),
+    ("flat", include_bytes!("../qoi_test_images/qoi_logo.qoi")),
+    ("index_heavy", include_bytes!("../qoi_test_images/dice.qoi")),
+];
+
+/// A smooth 1024x1024 gradient in which nearly every pixel is a small step from the last,
+/// so almost every op is a DIFF.
+fn diff_heavy() -> Vec<u8> {
+    let mut pixels = Vec::with_capacity(1024 * 1024 * 4);
+    for y in 0..1024_u32 {
+        for x in 0..1024_u32 {
+            pixels.extend_from_slice(&[x as u8, (x / 2 + y) as u8, (x + y / 2) as u8, 255]);
+        }
+    }
+    ImageData::from_rgba(1024, 1024, 3, 0, pixels)
+        .unwrap()
+        .to_qoi_bytes()
+}
+
+fn decode(c: &mut Criterion) {
+    let mut group = c.benchmark_group("decode");
+    let diff_heavy = diff_heavy();
+    let fixtures = FIXTURES
+        .into_iter()
+        .chain([("diff_heavy", diff_heavy.as_slice())]);
+    for (name, bytes) in fixtures {
+        let decoded_len = ImageData::decode_slice(bytes)
+            .unwrap()
+            .as_rgba_bytes()
+            .len();
+        group.throughput(Throughput::Bytes(decoded_len as u64));
+        group.bench_function(name, |b| b.iter(|| ImageData::decode_slice(bytes).unwrap()));
+    }
+    group.finish();
+}
+
+/// Many small files, where per-file allocation rather than pixel work dominates.
+fn batch(c: &mut Criterion) {
+    let photo = ImageData::decode_slice(FIXTURES[0].1).unwrap();
+    let (width, height) = photo.dimensions();
+    let files: Vec<Vec<u8>> = (0..height / 32)
+        .flat_map(|y| (0..width / 32).map(move |x| (x * 32, y * 32)))
+        .map(|(x, y)| photo.crop(x, y, 32, 32).unwrap().to_qoi_bytes())
+        .collect();
+    let mut group = c.benchmark_group("batch");
+    group.throughput(Throughput::Elements(files.len() as u64));
+    group.bench_function("fresh_buffers", |b| {
+        b.iter(|| {
+            for file in &files {
+                ImageData::decode(file.as_slice()).unwrap();
+            }
+        })
+    });
+    group.bench_function("decode_context", |b| {
+        let mut context = DecodeContext::new();
+        b.iter(|| {
+            for file in &files {
+                context.decode_file(file.as_slice()).unwrap();
+            }
+        })
+    });
+    group.finish();
+}
+
+/// The photo fixture re-encoded without index ops, decoded with and without the promise
+/// that there are none.
+fn no_index(c: &mut Criterion) {
+    let photo = ImageData::decode_slice(FIXTURES[0].1).unwrap();
+    let bytes = photo.to_qoi_bytes_with(EncodeOptions::default().use_index(false));
+    let mut group = c.benchmark_group("no_index");
+    group.throughput(Throughput::Bytes(photo.as_rgba_bytes().len() as u64));
+    for (name, assume_no_index) in [("index_table", false), ("assume_no_index", true)] {
+        let options = DecodeOptions::default().assume_no_index(assume_no_index);
+        group.bench_function(name, |b| {
+            b.iter(|| ImageData::decode_slice_with(&bytes, options).unwrap())
+        });
+    }
+    group.finish();
+}
+
+/// Checked decoding against [`ImageData::decode_trusted`] on the same files.
+fn trusted(c: &mut Criterion) {
+    let mut group = c.benchmark_group("trusted");
+    for (name, bytes) in FIXTURES {
+        let decoded_len = ImageData::decode_slice(bytes)
+            .unwrap()
+            .as_rgba_bytes()
+            .len();
+        group.throughput(Throughput::Bytes(decoded_len as u64));
+        group.bench_function(format!("{}/checked", name), |b| {
+            b.iter(|| ImageData::decode_slice(bytes).unwrap())
+        });
+        // SAFETY: the fixtures are known-good files, as the `decode_slice` above shows.
+        group.bench_function(format!("{}/trusted", name), |b| {
+            b.iter(|| unsafe { ImageData::decode_trusted(bytes) })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, decode, batch, no_index, trusted);
+criterion_main!(benches);
@@ -0,0 +1,71 @@
+use std::io::Write;
+
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use qoi_decoder::{ImageData, ImageDataBuilder, QoiWriter};
+
+/// A 4K RGBA gradient, so channel swaps can't be skipped for uniform pixels.
+fn image_4k() -> ImageData {
+    let (width, height) = (3840, 2160);
+    let image_data = (0..width * height)
+        .flat_map(|i| [(i % width) as u8, (i / width) as u8, (i % 251) as u8, 255])
+        .collect();
+    ImageDataBuilder::new(width, height)
+        .pixels(image_data)
+        .build()
+        .unwrap()
+}
+
+fn write_bgra(c: &mut Criterion) {
+    let image = image_4k();
+    let mut group = c.benchmark_group("write_bgra");
+    group.throughput(Throughput::Bytes(image.as_rgba_bytes().len() as u64));
+    let mut out = Vec::new();
+    // What write_tga did before swapping a word at a time: the same buffering, with the
+    // channels shuffled byte by byte. The header's contents don't matter, only its length.
+    group.bench_function("tga_naive", |b| {
+        b.iter(|| {
+            out.clear();
+            let mut bytes = Vec::with_capacity(18 + image.as_rgba_bytes().len());
+            bytes.extend_from_slice(&[0; 18]);
+            for chunk in image.as_rgba_bytes().chunks_exact(4) {
+                bytes.extend_from_slice(&[chunk[2], chunk[1], chunk[0], chunk[3]]);
+            }
+            out.write_all(&bytes).unwrap();
+        })
+    });
+    group.bench_function("bmp", |b| {
+        b.iter(|| {
+            out.clear();
+            image.write_bmp(&mut out).unwrap();
+        })
+    });
+    group.bench_function("tga", |b| {
+        b.iter(|| {
+            out.clear();
+            image.write_tga(&mut out).unwrap();
+        })
+    });
+    group.finish();
+}
+
+fn encode_qoi(c: &mut Criterion) {
+    let image = ImageData::decode_slice(include_bytes!("../qoi_test_images/kodim23.qoi")).unwrap();
+    let mut group = c.benchmark_group("encode_qoi");
+    group.throughput(Throughput::Bytes(image.as_rgba_bytes().len() as u64));
+    group.bench_function("to_qoi_bytes", |b| b.iter(|| image.to_qoi_bytes()));
+    group.bench_function("qoi_writer", |b| {
+        let (width, height) = image.dimensions();
+        b.iter(|| {
+            let mut writer =
+                QoiWriter::new(Vec::new(), width, height, image.channels(), 0).unwrap();
+            for row in image.rows() {
+                writer.write_rgba(row).unwrap();
+            }
+            writer.finish().unwrap()
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, write_bgra, encode_qoi);
+criterion_main!(benches);
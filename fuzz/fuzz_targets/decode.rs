@@ -0,0 +1,15 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use qoi_decoder::ImageData;
+
+// Decoding arbitrary bytes must only ever return `Ok` or `Err`, never panic.
+fuzz_target!(|data: &[u8]| {
+    if let Ok(image_data) = ImageData::decode_slice(data) {
+        let (width, height) = image_data.dimensions();
+        assert_eq!(
+            image_data.as_rgba_bytes().len(),
+            width as usize * height as usize * 4
+        );
+    }
+});
@@ -0,0 +1,32 @@
+#ifndef QOI_DECODER_H
+#define QOI_DECODER_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define QOI_OK 0
+#define QOI_ERROR_NULL_POINTER 1
+#define QOI_ERROR_BAD_MAGIC 2
+#define QOI_ERROR_UNEXPECTED_EOF 3
+#define QOI_ERROR_MISSING_END_MARKER 4
+#define QOI_ERROR_INVALID_HEADER 5
+#define QOI_ERROR_CORRUPT_DATA 6
+
+/* Decodes a QOI file into tightly packed RGBA8 pixels. On success returns QOI_OK,
+ * writes the dimensions to out_w/out_h and a width * height * 4 byte buffer to
+ * out_pixels, which must be released with qoi_free. */
+int qoi_decode(const uint8_t *data, size_t len, uint32_t *out_w, uint32_t *out_h,
+               uint8_t **out_pixels);
+
+/* Releases a buffer returned by qoi_decode. Passing NULL is a no-op. */
+void qoi_free(uint8_t *pixels);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
@@ -0,0 +1,13 @@
+use tokio::io::{AsyncBufRead, AsyncReadExt};
+
+use crate::{ImageData, QoiError};
+
+impl ImageData {
+    /// Like [`decode`](Self::decode), but reads the stream asynchronously. The whole stream
+    /// is read into memory first and then decoded with [`decode_slice`](Self::decode_slice).
+    pub async fn decode_async(mut reader: impl AsyncBufRead + Unpin) -> Result<Self, QoiError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Self::decode_slice(&bytes)
+    }
+}
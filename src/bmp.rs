@@ -0,0 +1,75 @@
+use std::io::Write;
+
+use crate::{swap_rb_inplace, ImageData, ImageEncoder, QoiError};
+
+const FILE_HEADER_LEN: u32 = 14;
+const INFO_HEADER_LEN: u32 = 40;
+const PIXELS_PER_METER: i32 = 2835;
+
+/// Writes an uncompressed bottom-up BMP: 24-bit BGR for three channels, 32-bit BGRA for
+/// four.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BmpEncoder;
+
+impl ImageEncoder for BmpEncoder {
+    fn encode(&self, image: &ImageData, out_buf: &mut dyn Write) -> Result<(), QoiError> {
+        let (width, height) = (image.header.width, image.header.height);
+        let bytes_per_pixel = match image.header.channels {
+            channels @ (3 | 4) => channels as usize,
+            channels => return Err(QoiError::InvalidChannels(channels)),
+        };
+        let too_large = || QoiError::DimensionsTooLarge { width, height };
+        let row_len = width as usize * bytes_per_pixel;
+        let padded_row_len = (row_len + 3) & !3;
+        let image_size = padded_row_len * height as usize;
+        let pixel_offset = FILE_HEADER_LEN + INFO_HEADER_LEN;
+        let file_size = u32::try_from(image_size)
+            .ok()
+            .and_then(|image_size| image_size.checked_add(pixel_offset))
+            .ok_or_else(too_large)?;
+        let width = i32::try_from(width).map_err(|_| too_large())?;
+        let height = i32::try_from(height).map_err(|_| too_large())?;
+
+        let mut bytes = Vec::with_capacity(file_size as usize);
+        bytes.extend_from_slice(b"BM");
+        bytes.extend_from_slice(&file_size.to_le_bytes());
+        bytes.extend_from_slice(&0_u32.to_le_bytes());
+        bytes.extend_from_slice(&pixel_offset.to_le_bytes());
+
+        bytes.extend_from_slice(&INFO_HEADER_LEN.to_le_bytes());
+        bytes.extend_from_slice(&width.to_le_bytes());
+        bytes.extend_from_slice(&height.to_le_bytes());
+        bytes.extend_from_slice(&1_u16.to_le_bytes());
+        bytes.extend_from_slice(&(bytes_per_pixel as u16 * 8).to_le_bytes());
+        bytes.extend_from_slice(&0_u32.to_le_bytes());
+        bytes.extend_from_slice(&(image_size as u32).to_le_bytes());
+        bytes.extend_from_slice(&PIXELS_PER_METER.to_le_bytes());
+        bytes.extend_from_slice(&PIXELS_PER_METER.to_le_bytes());
+        bytes.extend_from_slice(&0_u32.to_le_bytes());
+        bytes.extend_from_slice(&0_u32.to_le_bytes());
+
+        let stride = width as usize * 4;
+        for y in (0..height as usize).rev() {
+            let row = &image.image_data[y * stride..(y + 1) * stride];
+            if bytes_per_pixel == 4 {
+                let start = bytes.len();
+                bytes.extend_from_slice(row);
+                swap_rb_inplace(&mut bytes[start..]);
+            } else {
+                for chunk in row.chunks_exact(4) {
+                    bytes.extend_from_slice(&[chunk[2], chunk[1], chunk[0]]);
+                }
+            }
+            bytes.resize(bytes.len() + padded_row_len - row_len, 0);
+        }
+        out_buf.write_all(&bytes)?;
+        Ok(())
+    }
+}
+
+impl ImageData {
+    /// Shorthand for [`write_with`](Self::write_with) and [`BmpEncoder`].
+    pub fn write_bmp(&self, mut out_buf: impl Write) -> Result<(), QoiError> {
+        self.write_with(&BmpEncoder, &mut out_buf)
+    }
+}
@@ -0,0 +1,75 @@
+use alloc::{vec, vec::Vec};
+
+use crate::{ImageData, Pixel, QOIHeader, QoiError};
+
+enum Contents {
+    Pixels(Vec<u8>),
+    Solid(Pixel),
+}
+
+/// Builds an [`ImageData`] without a QOI file. Defaults to four channels, the sRGB
+/// colorspace and transparent black pixels.
+pub struct ImageDataBuilder {
+    width: u32,
+    height: u32,
+    channels: u8,
+    colorspace: u8,
+    contents: Contents,
+}
+
+impl ImageDataBuilder {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            channels: 4,
+            colorspace: 0,
+            contents: Contents::Solid(Pixel::rgba(0, 0, 0, 0)),
+        }
+    }
+
+    pub fn channels(mut self, channels: u8) -> Self {
+        self.channels = channels;
+        self
+    }
+
+    pub fn colorspace(mut self, colorspace: u8) -> Self {
+        self.colorspace = colorspace;
+        self
+    }
+
+    /// Uses tightly packed, row-major RGBA8 pixels, replacing any earlier `fill`.
+    pub fn pixels(mut self, image_data: Vec<u8>) -> Self {
+        self.contents = Contents::Pixels(image_data);
+        self
+    }
+
+    /// Sets every pixel to `pixel`, replacing any earlier `pixels`.
+    pub fn fill(mut self, pixel: Pixel) -> Self {
+        self.contents = Contents::Solid(pixel);
+        self
+    }
+
+    pub fn build(self) -> Result<ImageData, QoiError> {
+        let (image_data, fill) = match self.contents {
+            Contents::Pixels(image_data) => (image_data, None),
+            Contents::Solid(pixel) => {
+                let header =
+                    QOIHeader::new(self.width, self.height, self.channels, self.colorspace);
+                header.validate()?;
+                (vec![0; header.image_data_len()?], Some(pixel))
+            }
+        };
+        let mut image = ImageData::from_rgba(
+            self.width,
+            self.height,
+            self.channels,
+            self.colorspace,
+            image_data,
+        )?;
+        if let Some(pixel) = fill {
+            image.fill(pixel);
+        }
+        Ok(image)
+    }
+}
@@ -0,0 +1,52 @@
+use crate::{op_len, op_pixels, ImageData, QOIHeader, QoiError, HEADER_LEN, MAX_RUN};
+
+impl ImageData {
+    /// Encodes like [`to_qoi_bytes`](Self::to_qoi_bytes), and also returns a CRC-32 for each
+    /// row of the image, kept apart from the file so that it stays a plain QOI file. Each
+    /// row's checksum covers the ops that start in that row, so corrupting a byte of an op
+    /// changes only its row's checksum; the header and end marker aren't covered. A row that
+    /// lies entirely within a run started earlier has no ops, and a checksum of 0.
+    /// [`row_checksums`] recomputes the checksums from received bytes for comparison.
+    pub fn encode_with_row_checksums(&self) -> (Vec<u8>, Vec<u32>) {
+        let bytes = self.to_qoi_bytes();
+        let checksums = row_checksums(&bytes).expect("the encoder's output is well-formed");
+        (bytes, checksums)
+    }
+}
+
+/// The per-row checksums described in [`ImageData::encode_with_row_checksums`], computed
+/// from an encoded file. Only the op boundaries are needed, so nothing is decoded. A
+/// corrupted op code can shift every boundary after it, in which case the following rows
+/// mismatch too.
+pub fn row_checksums(qoi_bytes: &[u8]) -> Result<Vec<u32>, QoiError> {
+    let (ops, header) =
+        QOIHeader::parse(qoi_bytes).map_err(|e| QoiError::from_nom(e, 0, QoiError::BadMagic))?;
+    header.validate()?;
+    let width = header.width as u64;
+    let total_pixels = header.image_data_len()? as u64 / 4;
+    // No op byte covers more than one full run, so a tiny file claiming a huge height
+    // can't make us reserve a checksum for every declared row up front.
+    let coverable_rows = (ops.len() as u64 * MAX_RUN as u64 / width.max(1)).saturating_add(1);
+    let mut checksums = Vec::with_capacity(coverable_rows.min(header.height as u64) as usize);
+    let mut hasher = crc32fast::Hasher::new();
+    let (mut pos, mut pixel) = (0, 0);
+    while pixel < total_pixels {
+        let row = (pixel / width) as usize;
+        while checksums.len() < row {
+            checksums.push(core::mem::take(&mut hasher).finalize());
+        }
+        let op = ops
+            .get(pos)
+            .and_then(|&op_code| ops.get(pos..pos + op_len(op_code)))
+            .ok_or(QoiError::UnexpectedEof {
+                offset: HEADER_LEN + pos,
+            })?;
+        hasher.update(op);
+        pos += op.len();
+        pixel += op_pixels(op[0]);
+    }
+    while checksums.len() < header.height as usize {
+        checksums.push(core::mem::take(&mut hasher).finalize());
+    }
+    Ok(checksums)
+}
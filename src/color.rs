@@ -0,0 +1,220 @@
+use alloc::vec::Vec;
+
+use crate::{ImageData, Pixel, QOIHeader, QoiError};
+
+/// Rec. 709 luma weights in 16.16 fixed point, rounded so they sum to exactly 1.0.
+const LUMA_R: u32 = 13933;
+const LUMA_G: u32 = 46871;
+const LUMA_B: u32 = 4732;
+
+/// Byte order for [`ImageData::to_rgb565`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Endian {
+    #[default]
+    Little,
+    Big,
+}
+
+impl ImageData {
+    /// Packs each pixel into 16-bit RGB565, row-major, for displays that take it directly.
+    /// Each channel keeps its top bits (5 for red and blue, 6 for green) and the rest are
+    /// truncated rather than rounded or dithered; alpha is dropped. Every value is stored so
+    /// that its bytes in memory are in `endian` order, whatever the host's own byte order,
+    /// so the buffer can go straight out over SPI or into a framebuffer as is.
+    pub fn to_rgb565(&self, endian: Endian) -> Vec<u16> {
+        self.image_data
+            .chunks_exact(4)
+            .map(|chunk| {
+                let rgb565 = (chunk[0] as u16 >> 3) << 11
+                    | (chunk[1] as u16 >> 2) << 5
+                    | chunk[2] as u16 >> 3;
+                match endian {
+                    Endian::Little => rgb565.to_le(),
+                    Endian::Big => rgb565.to_be(),
+                }
+            })
+            .collect()
+    }
+
+    /// Converts to RGBA floats in `[0.0, 1.0]`, row-major, four per pixel. Each channel is
+    /// divided by 255. With `linearize` set and an sRGB image (colorspace 0), R, G and B
+    /// also go through the sRGB decoding curve, giving linear light as shaders expect;
+    /// alpha is always linear and left as is. Linear images (colorspace 1) are never
+    /// converted.
+    #[cfg(feature = "std")]
+    pub fn to_rgba_f32(&self, linearize: bool) -> Vec<f32> {
+        let linearize = linearize && self.header.colorspace == 0;
+        let normalized: [f32; 256] = core::array::from_fn(|v| v as f32 / 255.0);
+        let color = if linearize {
+            normalized.map(srgb_to_linear)
+        } else {
+            normalized
+        };
+        self.image_data
+            .chunks_exact(4)
+            .flat_map(|chunk| {
+                [
+                    color[chunk[0] as usize],
+                    color[chunk[1] as usize],
+                    color[chunk[2] as usize],
+                    normalized[chunk[3] as usize],
+                ]
+            })
+            .collect()
+    }
+
+    /// Returns a copy with each pixel's R, G and B replaced by its Rec. 709 luma. Alpha is
+    /// kept as is.
+    pub fn to_grayscale(&self) -> Self {
+        let image_data: Vec<u8> = self
+            .image_data
+            .chunks_exact(4)
+            .flat_map(|chunk| {
+                let luma = (LUMA_R * chunk[0] as u32
+                    + LUMA_G * chunk[1] as u32
+                    + LUMA_B * chunk[2] as u32
+                    + (1 << 15))
+                    >> 16;
+                let luma = luma as u8;
+                [luma, luma, luma, chunk[3]]
+            })
+            .collect();
+        let header = QOIHeader::new(
+            self.header.width,
+            self.header.height,
+            self.header.channels,
+            self.header.colorspace,
+        );
+        Self { header, image_data }
+    }
+
+    /// Returns a three-channel image whose R, G and B are each pixel's alpha, so white is
+    /// opaque and black fully transparent. Three-channel sources give an all-white mask. The
+    /// mask is tagged linear (colorspace 1), as alpha is.
+    pub fn alpha_mask(&self) -> Self {
+        let image_data = self
+            .image_data
+            .chunks_exact(4)
+            .flat_map(|chunk| {
+                let a = if self.header.channels == 3 {
+                    255
+                } else {
+                    chunk[3]
+                };
+                [a, a, a, 255]
+            })
+            .collect();
+        let header = QOIHeader::new(self.header.width, self.header.height, 3, 1);
+        Self { header, image_data }
+    }
+
+    /// Composites `self` over `bottom` with the source-over operator on straight (not
+    /// premultiplied) alpha, rounding to the nearest value. The images must be the same
+    /// size. The result keeps `bottom`'s colorspace and has an alpha channel unless both
+    /// inputs are three-channel.
+    pub fn blend_over(&self, bottom: &ImageData) -> Result<Self, QoiError> {
+        if self.dimensions() != bottom.dimensions() {
+            return Err(QoiError::DimensionMismatch {
+                expected: bottom.dimensions(),
+                actual: self.dimensions(),
+            });
+        }
+        let image_data = self
+            .image_data
+            .chunks_exact(4)
+            .zip(bottom.image_data.chunks_exact(4))
+            .flat_map(|(top, bottom)| {
+                let (top_a, bottom_a) = (top[3] as u32, bottom[3] as u32);
+                // Output alpha scaled by 255, so the color division below stays exact.
+                let alpha = top_a * 255 + bottom_a * (255 - top_a);
+                if alpha == 0 {
+                    return [0; 4];
+                }
+                let blend = |c: usize| {
+                    let weighted =
+                        top[c] as u32 * top_a * 255 + bottom[c] as u32 * bottom_a * (255 - top_a);
+                    ((weighted + alpha / 2) / alpha) as u8
+                };
+                [blend(0), blend(1), blend(2), ((alpha + 127) / 255) as u8]
+            })
+            .collect();
+        let channels = if self.header.channels == 3 && bottom.header.channels == 3 {
+            3
+        } else {
+            4
+        };
+        let header = QOIHeader::new(
+            bottom.header.width,
+            bottom.header.height,
+            channels,
+            bottom.header.colorspace,
+        );
+        Ok(Self { header, image_data })
+    }
+
+    /// Scales R, G and B by alpha in place. Does nothing for three-channel images.
+    pub fn premultiply_alpha(&mut self) {
+        if self.header.channels != 4 {
+            return;
+        }
+        for chunk in self.image_data.chunks_exact_mut(4) {
+            let a = chunk[3] as u32;
+            for c in &mut chunk[..3] {
+                *c = ((*c as u32 * a + 127) / 255) as u8;
+            }
+        }
+    }
+
+    /// Reverses [`premultiply_alpha`](Self::premultiply_alpha) in place, leaving fully
+    /// transparent pixels untouched. Does nothing for three-channel images.
+    pub fn unpremultiply_alpha(&mut self) {
+        if self.header.channels != 4 {
+            return;
+        }
+        for chunk in self.image_data.chunks_exact_mut(4) {
+            let a = chunk[3] as u32;
+            if a == 0 {
+                continue;
+            }
+            for c in &mut chunk[..3] {
+                *c = ((*c as u32 * 255 + a / 2) / a).min(255) as u8;
+            }
+        }
+    }
+
+    /// Sets every pixel to `pixel`. Three-channel images have no alpha to set, so their
+    /// pixels stay opaque.
+    pub fn fill(&mut self, mut pixel: Pixel) {
+        if self.header.channels == 3 {
+            pixel.a = 255;
+        }
+        let flat_pixel = pixel.flat();
+        self.image_data
+            .chunks_exact_mut(4)
+            .for_each(|chunk| chunk.copy_from_slice(&flat_pixel));
+    }
+
+    /// Replaces every pixel with `f(pixel)` in place, visiting them in row-major order. As
+    /// with [`fill`](Self::fill), three-channel images stay opaque whatever alpha `f` returns.
+    pub fn map_pixels(&mut self, mut f: impl FnMut(Pixel) -> Pixel) {
+        let opaque = self.header.channels == 3;
+        for chunk in self.image_data.chunks_exact_mut(4) {
+            let mut pixel = f(Pixel::from_slice(chunk));
+            if opaque {
+                pixel.a = 255;
+            }
+            chunk.copy_from_slice(&pixel.flat());
+        }
+    }
+}
+
+/// The sRGB decoding curve (IEC 61966-2-1), from an encoded value in `[0.0, 1.0]` to
+/// linear light: a short linear segment near black, then a 2.4 power curve.
+#[cfg(feature = "std")]
+fn srgb_to_linear(c: f32) -> f32 {
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
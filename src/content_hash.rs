@@ -0,0 +1,20 @@
+use xxhash_rust::xxh3::Xxh3;
+
+use crate::ImageData;
+
+impl ImageData {
+    /// A 64-bit hash of the decoded pixels together with the width, height and channel
+    /// count, for spotting duplicates and validating caches. Images that decode to the same
+    /// pixels hash the same however they were encoded, and the result doesn't depend on the
+    /// platform or crate version. The colorspace isn't included. This is XXH3, a fast
+    /// non-cryptographic hash, so it offers no protection against deliberately crafted
+    /// collisions.
+    pub fn content_hash(&self) -> u64 {
+        let mut hasher = Xxh3::new();
+        hasher.update(&self.header.width.to_le_bytes());
+        hasher.update(&self.header.height.to_le_bytes());
+        hasher.update(&[self.header.channels]);
+        hasher.update(&self.image_data);
+        hasher.digest()
+    }
+}
@@ -0,0 +1,55 @@
+use std::io::Read;
+
+use crate::{decode_bytes, DecodeOptions, ImageData, QOIHeader, QoiError};
+
+/// Decodes many files one after another, reusing the same input and pixel buffers so that
+/// after the first few files decoding no longer allocates.
+///
+/// The decoded image lives inside the context: [`decode_file`](Self::decode_file) returns a
+/// borrow of it, which must be dropped before the next call overwrites it. Clone the image
+/// (or copy out what you need) to keep it longer. The buffers only ever grow, so a context
+/// that once decoded a huge image keeps that memory until it is dropped.
+pub struct DecodeContext {
+    input: Vec<u8>,
+    image: ImageData,
+}
+
+impl DecodeContext {
+    pub fn new() -> Self {
+        Self {
+            input: Vec::new(),
+            image: ImageData {
+                header: QOIHeader::new(0, 0, 4, 0),
+                image_data: Vec::new(),
+            },
+        }
+    }
+
+    pub fn decode_file(&mut self, reader: impl Read) -> Result<&ImageData, QoiError> {
+        self.decode_file_with(reader, DecodeOptions::default())
+    }
+
+    pub fn decode_file_with(
+        &mut self,
+        mut reader: impl Read,
+        options: DecodeOptions,
+    ) -> Result<&ImageData, QoiError> {
+        self.input.clear();
+        reader.read_to_end(&mut self.input)?;
+        self.image.header = decode_bytes(
+            &self.input,
+            &mut self.image.image_data,
+            options,
+            false,
+            |_, _| {},
+            |_, _| {},
+        )?;
+        Ok(&self.image)
+    }
+}
+
+impl Default for DecodeContext {
+    fn default() -> Self {
+        Self::new()
+    }
+}
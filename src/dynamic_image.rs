@@ -0,0 +1,16 @@
+use image::{DynamicImage, RgbImage, RgbaImage};
+
+use crate::ImageData;
+
+impl ImageData {
+    pub fn to_dynamic_image(&self) -> DynamicImage {
+        let (width, height) = self.dimensions();
+        let image = match self.header.channels {
+            3 => RgbImage::from_raw(width, height, self.to_rgb_bytes()).map(DynamicImage::from),
+            _ => {
+                RgbaImage::from_raw(width, height, self.image_data.clone()).map(DynamicImage::from)
+            }
+        };
+        image.expect("pixel buffer matches the header dimensions")
+    }
+}
@@ -0,0 +1,30 @@
+use std::io::Write;
+
+use crate::{EncodeOptions, ImageData, QoiError};
+
+/// An output format. Implement this to add formats of your own; they work with
+/// [`ImageData::write_with`] exactly like the built-in ones.
+pub trait ImageEncoder {
+    fn encode(&self, image: &ImageData, out: &mut dyn Write) -> Result<(), QoiError>;
+}
+
+/// Writes QOI with the given options.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct QoiEncoder(pub EncodeOptions);
+
+impl ImageEncoder for QoiEncoder {
+    fn encode(&self, image: &ImageData, out: &mut dyn Write) -> Result<(), QoiError> {
+        out.write_all(&image.to_qoi_bytes_with(self.0))?;
+        Ok(())
+    }
+}
+
+impl ImageData {
+    pub fn write_with(
+        &self,
+        encoder: &(impl ImageEncoder + ?Sized),
+        mut out_buf: impl Write,
+    ) -> Result<(), QoiError> {
+        encoder.encode(self, &mut out_buf)
+    }
+}
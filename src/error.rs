@@ -0,0 +1,244 @@
+use core::fmt::{self, Display};
+#[cfg(feature = "std")]
+use std::{error::Error, io};
+
+use nom::error::ErrorKind;
+
+#[derive(Debug)]
+pub enum QoiError {
+    BadMagic,
+    /// The input ends partway through the header or the op starting at byte `offset`.
+    UnexpectedEof {
+        offset: usize,
+    },
+    /// The end marker isn't at byte `offset`, just past the last op.
+    MissingEndMarker {
+        offset: usize,
+    },
+    TrailingData(usize),
+    InvalidChannels(u8),
+    InvalidColorspace(u8),
+    DimensionsTooLarge {
+        width: u32,
+        height: u32,
+    },
+    TooManyPixels,
+    /// The header declares `expected` pixels, but the ops form a complete image of `got`
+    /// pixels, ending at the end marker; or, when encoding, `got` pixels were written.
+    PixelCountMismatch {
+        expected: u64,
+        got: u64,
+    },
+    UnexpectedIndexOp,
+    RegionOutOfBounds {
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    },
+    BufferSizeMismatch {
+        expected: usize,
+        actual: usize,
+    },
+    DimensionMismatch {
+        expected: (u32, u32),
+        actual: (u32, u32),
+    },
+    InvalidPaletteSize(usize),
+    PaletteIndexOutOfRange {
+        index: u8,
+        palette_len: usize,
+    },
+    /// [`ImageData::peek_header`](crate::ImageData::peek_header) found only `buffered`
+    /// bytes in the reader's buffer, too few for the header. The input may end there, or
+    /// the rest may not have arrived yet.
+    #[cfg(feature = "std")]
+    HeaderNotBuffered {
+        buffered: usize,
+    },
+    #[cfg(feature = "std")]
+    UnsupportedPng(png::ColorType, png::BitDepth),
+    #[cfg(feature = "std")]
+    Io(io::Error),
+    #[cfg(feature = "std")]
+    PngEncode(png::EncodingError),
+    #[cfg(feature = "std")]
+    PngDecode(png::DecodingError),
+}
+
+/// Something wrong with a file that a lenient decode worked around; see
+/// [`ImageData::decode_with_warnings`](crate::ImageData::decode_with_warnings).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecodeWarning {
+    /// Bytes left over after the end marker.
+    TrailingBytes(usize),
+    /// The end marker isn't where the ops end, or isn't there at all.
+    MissingEndMarker,
+    /// The ops describe a different number of pixels than the header declares. When they
+    /// fall short, `got` counts the pixels decoded and the rest are transparent black; when
+    /// a run overshoots, `got` counts up to the end of that run and the excess is dropped.
+    PixelCountMismatch { expected: u64, got: u64 },
+    /// A `QOI_OP_INDEX` at byte `offset` although
+    /// [`DecodeOptions::assume_no_index`](crate::DecodeOptions::assume_no_index) promised
+    /// there were none. Decoding stopped there and the remaining pixels are transparent
+    /// black.
+    UnexpectedIndexOp { offset: usize },
+}
+
+impl QoiError {
+    /// Maps a failed parse of something starting at byte `offset` to `on_tag_mismatch` if
+    /// a tag didn't match, and to `UnexpectedEof` otherwise.
+    pub(crate) fn from_nom(
+        err: nom::Err<nom::error::Error<&[u8]>>,
+        offset: usize,
+        on_tag_mismatch: Self,
+    ) -> Self {
+        match err {
+            nom::Err::Error(e) | nom::Err::Failure(e) if e.code == ErrorKind::Tag => {
+                on_tag_mismatch
+            }
+            _ => Self::UnexpectedEof { offset },
+        }
+    }
+}
+
+impl Display for QoiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::BadMagic => write!(f, "missing \"qoif\" magic bytes"),
+            Self::UnexpectedEof { offset } => {
+                write!(f, "unexpected end of input at byte {}", offset)
+            }
+            Self::MissingEndMarker { offset } => write!(f, "missing end marker at byte {}", offset),
+            Self::TrailingData(len) => {
+                write!(f, "{} bytes of trailing data after the end marker", len)
+            }
+            Self::InvalidChannels(channels) => write!(f, "invalid channel count: {}", channels),
+            Self::InvalidColorspace(colorspace) => write!(f, "invalid colorspace: {}", colorspace),
+            Self::DimensionsTooLarge { width, height } => {
+                write!(f, "image dimensions too large: {}x{}", width, height)
+            }
+            Self::TooManyPixels => write!(f, "more pixels than the header declares"),
+            Self::PixelCountMismatch { expected, got } => write!(
+                f,
+                "header declares {} pixels but the data holds {}",
+                expected, got
+            ),
+            Self::UnexpectedIndexOp => write!(f, "index op in a stream assumed to have none"),
+            Self::RegionOutOfBounds {
+                x,
+                y,
+                width,
+                height,
+            } => write!(
+                f,
+                "region {}x{} at ({}, {}) exceeds the image bounds",
+                width, height, x, y
+            ),
+            Self::BufferSizeMismatch { expected, actual } => write!(
+                f,
+                "pixel buffer is {} bytes but the dimensions require {}",
+                actual, expected
+            ),
+            Self::DimensionMismatch { expected, actual } => write!(
+                f,
+                "image is {}x{} but {}x{} was expected",
+                actual.0, actual.1, expected.0, expected.1
+            ),
+            Self::InvalidPaletteSize(len) => {
+                write!(f, "palette has {} entries; it needs 1 to 256", len)
+            }
+            Self::PaletteIndexOutOfRange { index, palette_len } => write!(
+                f,
+                "palette index {} is out of range for a {}-entry palette",
+                index, palette_len
+            ),
+            #[cfg(feature = "std")]
+            Self::HeaderNotBuffered { buffered } => write!(
+                f,
+                "only {} bytes buffered; the header needs {}",
+                buffered,
+                crate::HEADER_LEN
+            ),
+            #[cfg(feature = "std")]
+            Self::UnsupportedPng(color_type, bit_depth) => write!(
+                f,
+                "unsupported png format: {:?} at {:?} bit depth",
+                color_type, bit_depth
+            ),
+            #[cfg(feature = "std")]
+            Self::Io(e) => write!(f, "io error: {}", e),
+            #[cfg(feature = "std")]
+            Self::PngEncode(e) => write!(f, "png encoding error: {}", e),
+            #[cfg(feature = "std")]
+            Self::PngDecode(e) => write!(f, "png decoding error: {}", e),
+        }
+    }
+}
+
+impl Display for DecodeWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TrailingBytes(len) => {
+                write!(f, "{} bytes of trailing data after the end marker", len)
+            }
+            Self::MissingEndMarker => write!(f, "missing end marker"),
+            Self::PixelCountMismatch { expected, got } => write!(
+                f,
+                "header declares {} pixels but the data holds {}",
+                expected, got
+            ),
+            Self::UnexpectedIndexOp { offset } => {
+                write!(
+                    f,
+                    "index op at byte {} in a stream assumed to have none",
+                    offset
+                )
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for QoiError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            Self::PngEncode(e) => Some(e),
+            Self::PngDecode(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<io::Error> for QoiError {
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+/// I/O errors come back out as they went in; everything else is `InvalidData`.
+#[cfg(feature = "std")]
+impl From<QoiError> for io::Error {
+    fn from(e: QoiError) -> Self {
+        match e {
+            QoiError::Io(e) => e,
+            e => io::Error::new(io::ErrorKind::InvalidData, e),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<png::EncodingError> for QoiError {
+    fn from(e: png::EncodingError) -> Self {
+        Self::PngEncode(e)
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<png::DecodingError> for QoiError {
+    fn from(e: png::DecodingError) -> Self {
+        Self::PngDecode(e)
+    }
+}
@@ -0,0 +1,83 @@
+//! C entry points. Build a linkable library with
+//! `cargo rustc --lib --release --features ffi --crate-type cdylib` (or `staticlib`) and include
+//! `include/qoi_decoder.h`.
+
+use core::{ffi::c_int, mem::size_of, ptr, slice};
+
+use crate::{ImageData, QoiError};
+
+pub const QOI_OK: c_int = 0;
+pub const QOI_ERROR_NULL_POINTER: c_int = 1;
+pub const QOI_ERROR_BAD_MAGIC: c_int = 2;
+pub const QOI_ERROR_UNEXPECTED_EOF: c_int = 3;
+pub const QOI_ERROR_MISSING_END_MARKER: c_int = 4;
+pub const QOI_ERROR_INVALID_HEADER: c_int = 5;
+pub const QOI_ERROR_CORRUPT_DATA: c_int = 6;
+
+/// Every buffer handed out is preceded by its length so `qoi_free` can rebuild the exact
+/// allocation without the caller passing the size back.
+const LEN_PREFIX: usize = size_of::<usize>();
+
+fn error_code(err: &QoiError) -> c_int {
+    match err {
+        QoiError::BadMagic => QOI_ERROR_BAD_MAGIC,
+        QoiError::UnexpectedEof { .. } => QOI_ERROR_UNEXPECTED_EOF,
+        QoiError::MissingEndMarker { .. } => QOI_ERROR_MISSING_END_MARKER,
+        QoiError::InvalidChannels(_)
+        | QoiError::InvalidColorspace(_)
+        | QoiError::DimensionsTooLarge { .. } => QOI_ERROR_INVALID_HEADER,
+        _ => QOI_ERROR_CORRUPT_DATA,
+    }
+}
+
+/// Decodes `len` bytes at `data` into tightly packed RGBA8 pixels. On success the
+/// dimensions are written to `out_w`/`out_h`, and `out_pixels` receives a buffer of
+/// `width * height * 4` bytes that must be released with [`qoi_free`].
+///
+/// # Safety
+///
+/// `data` must point to `len` readable bytes, and the out pointers must be valid for
+/// writes.
+#[no_mangle]
+pub unsafe extern "C" fn qoi_decode(
+    data: *const u8,
+    len: usize,
+    out_w: *mut u32,
+    out_h: *mut u32,
+    out_pixels: *mut *mut u8,
+) -> c_int {
+    if data.is_null() || out_w.is_null() || out_h.is_null() || out_pixels.is_null() {
+        return QOI_ERROR_NULL_POINTER;
+    }
+    let image_data = match ImageData::decode_slice(slice::from_raw_parts(data, len)) {
+        Ok(image_data) => image_data,
+        Err(e) => return error_code(&e),
+    };
+    let (width, height) = image_data.dimensions();
+    let pixels = image_data.as_rgba_bytes();
+    let mut buf = Vec::with_capacity(LEN_PREFIX + pixels.len());
+    buf.extend_from_slice(&pixels.len().to_ne_bytes());
+    buf.extend_from_slice(pixels);
+    let buf = Box::into_raw(buf.into_boxed_slice()) as *mut u8;
+    *out_w = width;
+    *out_h = height;
+    *out_pixels = buf.add(LEN_PREFIX);
+    QOI_OK
+}
+
+/// Releases a pixel buffer returned by [`qoi_decode`]. Passing null is a no-op.
+///
+/// # Safety
+///
+/// `pixels` must be null or a pointer obtained from `qoi_decode` that hasn't been freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn qoi_free(pixels: *mut u8) {
+    if pixels.is_null() {
+        return;
+    }
+    let buf = pixels.sub(LEN_PREFIX);
+    let mut len = [0; LEN_PREFIX];
+    ptr::copy_nonoverlapping(buf, len.as_mut_ptr(), LEN_PREFIX);
+    let len = LEN_PREFIX + usize::from_ne_bytes(len);
+    drop(Box::from_raw(ptr::slice_from_raw_parts_mut(buf, len)));
+}
@@ -1,27 +1,102 @@
-#![feature(array_chunks)]
+#![cfg_attr(not(feature = "std"), no_std)]
 
-use std::{
-    error::Error,
-    io::{Read, Write},
-};
+extern crate alloc;
+
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::io::{self, BufRead, Read, Write};
 
 use derive_new::new;
 use nom::{
-    bits::{bits, complete::take},
     bytes::complete::tag,
-    combinator::map,
     number::complete::{be_u32, be_u8},
     sequence::{preceded, tuple},
-    IResult, Parser,
+    IResult,
 };
 use qoi_op_codes::*;
+#[cfg(feature = "tokio")]
+mod async_io;
+#[cfg(feature = "std")]
+mod bmp;
+mod builder;
+#[cfg(feature = "std")]
+mod checksum;
+mod color;
+mod content_hash;
+#[cfg(feature = "std")]
+mod context;
+#[cfg(feature = "image")]
+mod dynamic_image;
+#[cfg(feature = "std")]
+mod encoder;
+mod error;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+mod packed;
+mod palette;
+#[cfg(feature = "rayon")]
+mod parallel;
+#[cfg(feature = "std")]
+mod png_io;
+#[cfg(feature = "std")]
+mod ppm;
 mod qoi_op_codes;
+#[cfg(feature = "std")]
+mod sequence;
+mod stats;
+#[cfg(feature = "std")]
+mod stream;
+#[cfg(feature = "std")]
+mod tga;
+mod transform;
+mod trusted;
+mod verify;
+#[cfg(feature = "wasm")]
+mod wasm;
 
+#[cfg(feature = "std")]
+pub use bmp::BmpEncoder;
+pub use builder::ImageDataBuilder;
+#[cfg(feature = "std")]
+pub use checksum::row_checksums;
+pub use color::Endian;
+#[cfg(feature = "std")]
+pub use context::DecodeContext;
+#[cfg(feature = "std")]
+pub use encoder::{ImageEncoder, QoiEncoder};
+pub use error::{DecodeWarning, QoiError};
+pub use packed::PackedImage;
+#[cfg(feature = "std")]
+pub use png_io::{PngBitDepth, PngCompression, PngEncoder, PngOptions};
+#[cfg(feature = "std")]
+pub use ppm::PpmEncoder;
+#[cfg(feature = "std")]
+pub use sequence::QoiSequence;
+pub use stats::{Histogram, OpStats};
+#[cfg(feature = "std")]
+pub use stream::{QoiDecoder, QoiWriter, RgbaReader};
+#[cfg(feature = "std")]
+pub use tga::TgaEncoder;
+pub use verify::{verify_roundtrip, ImageDiff, Roundtrip};
+#[cfg(feature = "wasm")]
+pub use wasm::{decode_to_rgba, DecodedImage};
+
+const MAGIC: &[u8; 4] = b"qoif";
+const HEADER_LEN: usize = 14;
 const END_MARKER: [u8; 8] = [0b00, 0b00, 0b00, 0b00, 0b00, 0b00, 0b00, 0b01];
+const MAX_RUN: u8 = 62;
+const MAX_PIXELS: u64 = 400_000_000;
+const PROGRESS_INTERVAL: usize = 1 << 20;
 
-#[allow(dead_code)]
-#[derive(new)]
-struct QOIHeader {
+/// A cheap check that `bytes` starts with the `qoif` magic and is long enough to hold a
+/// header and an end marker. Doesn't validate anything else.
+pub fn is_qoi(bytes: &[u8]) -> bool {
+    bytes.len() >= HEADER_LEN + END_MARKER.len() && bytes.starts_with(MAGIC)
+}
+
+#[derive(new, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct QOIHeader {
     width: u32,
     height: u32,
     channels: u8,
@@ -29,33 +104,108 @@ struct QOIHeader {
 }
 
 impl QOIHeader {
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn channels(&self) -> u8 {
+        self.channels
+    }
+
+    pub fn colorspace(&self) -> u8 {
+        self.colorspace
+    }
+
     fn parse(input: &[u8]) -> IResult<&[u8], Self> {
         let (input, (width, height, channels, colorspace)) =
-            preceded(tag(b"qoif"), tuple((be_u32, be_u32, be_u8, be_u8)))(input)?;
+            preceded(tag(MAGIC), tuple((be_u32, be_u32, be_u8, be_u8)))(input)?;
         Ok((input, Self::new(width, height, channels, colorspace)))
     }
+
+    fn validate(&self) -> Result<(), QoiError> {
+        if !(3..=4).contains(&self.channels) {
+            return Err(QoiError::InvalidChannels(self.channels));
+        }
+        if self.colorspace > 1 {
+            return Err(QoiError::InvalidColorspace(self.colorspace));
+        }
+        Ok(())
+    }
+
+    fn image_data_len(&self) -> Result<usize, QoiError> {
+        let too_large = || QoiError::DimensionsTooLarge {
+            width: self.width,
+            height: self.height,
+        };
+        let pixels = (self.width as u64)
+            .checked_mul(self.height as u64)
+            .filter(|&pixels| pixels <= MAX_PIXELS)
+            .ok_or_else(too_large)?;
+        usize::try_from(pixels)
+            .ok()
+            .and_then(|pixels| pixels.checked_mul(4))
+            .ok_or_else(too_large)
+    }
+
+    fn write(&self, bytes: &mut Vec<u8>) {
+        bytes.extend_from_slice(MAGIC);
+        bytes.extend_from_slice(&self.width.to_be_bytes());
+        bytes.extend_from_slice(&self.height.to_be_bytes());
+        bytes.push(self.channels);
+        bytes.push(self.colorspace);
+    }
 }
 
-#[derive(new, Clone, Copy)]
-struct Pixel {
-    r: u8,
-    g: u8,
-    b: u8,
-    a: u8,
+#[derive(new, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pixel {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub a: u8,
 }
 
 impl Pixel {
-    fn hash(&self) -> usize {
+    /// An opaque pixel.
+    pub const fn rgb(r: u8, g: u8, b: u8) -> Self {
+        Self::rgba(r, g, b, 255)
+    }
+
+    pub const fn rgba(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Self { r, g, b, a }
+    }
+
+    fn from_slice(chunk: &[u8]) -> Self {
+        Self::new(chunk[0], chunk[1], chunk[2], chunk[3])
+    }
+
+    /// The pixel's slot in the 64-entry color index array: exactly the QOI spec's
+    /// `(r * 3 + g * 5 + b * 7 + a * 11) % 64`. Distinct colors often share a slot, in which
+    /// case the most recently seen one wins and the earlier color is no longer indexable.
+    pub const fn hash(&self) -> usize {
         (self.r as usize * 3 + self.g as usize * 5 + self.b as usize * 7 + self.a as usize * 11)
             % 64
     }
 
+    /// Adds the deltas to R, G and B, each wrapping independently. All four channels are
+    /// added as one word with the carries between bytes masked off, which the compiler
+    /// turns into a handful of scalar ops rather than three separate byte adds.
     fn wrapping_add(&self, r: u8, g: u8, b: u8) -> Self {
-        Self::new(
-            self.r.wrapping_add(r),
-            self.g.wrapping_add(g),
-            self.b.wrapping_add(b),
-            self.a,
+        const LOW_BITS: u32 = 0x7f7f_7f7f;
+        let pixel = u32::from_le_bytes(self.flat());
+        let delta = u32::from_le_bytes([r, g, b, 0]);
+        let sum = ((pixel & LOW_BITS) + (delta & LOW_BITS)) ^ ((pixel ^ delta) & !LOW_BITS);
+        Self::from(sum.to_le_bytes())
+    }
+
+    fn wrapping_diff(&self, prev: &Self) -> (i8, i8, i8) {
+        (
+            self.r.wrapping_sub(prev.r) as i8,
+            self.g.wrapping_sub(prev.g) as i8,
+            self.b.wrapping_sub(prev.b) as i8,
         )
     }
 
@@ -64,86 +214,933 @@ impl Pixel {
     }
 }
 
+impl From<[u8; 4]> for Pixel {
+    fn from([r, g, b, a]: [u8; 4]) -> Self {
+        Self::new(r, g, b, a)
+    }
+}
+
+impl From<Pixel> for [u8; 4] {
+    fn from(pixel: Pixel) -> Self {
+        pixel.flat()
+    }
+}
+
+/// Controls how [`ImageData::decode_with`] treats malformed input. Start from
+/// [`DecodeOptions::default`] and change what you need with the setters, so that new options
+/// can be added without breaking callers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct DecodeOptions {
+    /// When set (the default), truncated input, runs past the declared pixel count and a
+    /// missing or trailed end marker are errors. Otherwise decoding stops once the pixel
+    /// buffer is full or the input runs out, and pixels that were never decoded are left as
+    /// transparent black.
+    pub strict: bool,
+    /// When cleared, decoding ends as soon as the declared number of pixels has been
+    /// produced and anything after it, end marker or not, is ignored. For QOI streams
+    /// embedded in containers that record their length and omit the marker.
+    pub require_end_marker: bool,
+    /// Promises that the file has no `QOI_OP_INDEX` ops, so the decoder can skip updating
+    /// the color index array after every pixel. An index op is then an error (or, when not
+    /// strict, where decoding stops). Only worth it for streams from encoders known never to
+    /// emit index ops.
+    pub assume_no_index: bool,
+}
+
+impl Default for DecodeOptions {
+    fn default() -> Self {
+        Self {
+            strict: true,
+            require_end_marker: true,
+            assume_no_index: false,
+        }
+    }
+}
+
+impl DecodeOptions {
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    pub fn require_end_marker(mut self, require_end_marker: bool) -> Self {
+        self.require_end_marker = require_end_marker;
+        self
+    }
+
+    pub fn assume_no_index(mut self, assume_no_index: bool) -> Self {
+        self.assume_no_index = assume_no_index;
+        self
+    }
+}
+
+/// The meaning of the header's colorspace byte.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Colorspace {
+    /// `0`: sRGB color channels with linear alpha.
+    Srgb = 0,
+    /// `1`: every channel linear.
+    Linear = 1,
+}
+
+/// Controls which ops [`ImageData::encode_with`] may emit, for comparing compression ratios,
+/// and which colorspace the header declares. Start from [`EncodeOptions::default`] and
+/// change what you need with the setters, so that new options can be added without breaking
+/// callers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct EncodeOptions {
+    /// Emit `QOI_OP_RGBA` for every pixel that isn't covered by a run or an index hit,
+    /// instead of the smaller `QOI_OP_RGB`, `QOI_OP_DIFF` and `QOI_OP_LUMA` ops.
+    pub force_rgba: bool,
+    /// Emit `QOI_OP_INDEX` for pixels found in the color index array. Decoders rebuild that
+    /// array from the pixels themselves, so turning this off needs no other changes to the
+    /// stream.
+    pub use_index: bool,
+    /// The longest run a single `QOI_OP_RUN` may cover, clamped to `1..=62`.
+    pub max_run: u8,
+    /// Overrides the image's colorspace in the written header; `None` keeps it.
+    pub colorspace: Option<Colorspace>,
+}
+
+impl Default for EncodeOptions {
+    fn default() -> Self {
+        Self {
+            force_rgba: false,
+            use_index: true,
+            max_run: MAX_RUN,
+            colorspace: None,
+        }
+    }
+}
+
+impl EncodeOptions {
+    pub fn force_rgba(mut self, force_rgba: bool) -> Self {
+        self.force_rgba = force_rgba;
+        self
+    }
+
+    pub fn use_index(mut self, use_index: bool) -> Self {
+        self.use_index = use_index;
+        self
+    }
+
+    pub fn max_run(mut self, max_run: u8) -> Self {
+        self.max_run = max_run;
+        self
+    }
+
+    pub fn colorspace(mut self, colorspace: Option<Colorspace>) -> Self {
+        self.colorspace = colorspace;
+        self
+    }
+}
+
+/// The result of [`ImageData::decode_partial`]: an image whose pixels past
+/// `decoded_pixels` could not be decoded and were left as transparent black.
+pub struct PartialImage {
+    image: ImageData,
+    decoded_pixels: u64,
+}
+
+impl PartialImage {
+    pub fn image(&self) -> &ImageData {
+        &self.image
+    }
+
+    pub fn into_image(self) -> ImageData {
+        self.image
+    }
+
+    /// Pixels recovered from the input, counted from the top-left in row-major order.
+    pub fn decoded_pixels(&self) -> u64 {
+        self.decoded_pixels
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.decoded_pixels == self.image.image_data.len() as u64 / 4
+    }
+}
+
+/// Two images are equal when their headers match and they hold the same pixels; see
+/// [`diff`](ImageData::diff) for where they differ.
+#[derive(Clone, PartialEq, Eq)]
 pub struct ImageData {
     header: QOIHeader,
     image_data: Vec<u8>,
 }
 
+impl core::fmt::Debug for ImageData {
+    /// Prints the header and the buffer length; the pixels themselves would swamp any log.
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("ImageData")
+            .field("width", &self.header.width)
+            .field("height", &self.header.height)
+            .field("channels", &self.header.channels)
+            .field("colorspace", &self.header.colorspace)
+            .field("image_data_len", &self.image_data.len())
+            .finish()
+    }
+}
+
 impl ImageData {
-    pub fn decode(mut input_buf: impl Read) -> Result<Self, Box<dyn Error>> {
+    /// Builds an image from tightly packed, row-major RGBA8 pixels.
+    pub fn from_rgba(
+        width: u32,
+        height: u32,
+        channels: u8,
+        colorspace: u8,
+        image_data: Vec<u8>,
+    ) -> Result<Self, QoiError> {
+        let header = QOIHeader::new(width, height, channels, colorspace);
+        header.validate()?;
+        let image_data_len = header.image_data_len()?;
+        if image_data.len() != image_data_len {
+            return Err(QoiError::BufferSizeMismatch {
+                expected: image_data_len,
+                actual: image_data.len(),
+            });
+        }
+        Ok(Self { header, image_data })
+    }
+
+    #[cfg(feature = "std")]
+    pub fn decode(input_buf: impl Read) -> Result<Self, QoiError> {
+        Self::decode_with(input_buf, DecodeOptions::default())
+    }
+
+    #[cfg(feature = "std")]
+    pub fn decode_with(mut input_buf: impl Read, options: DecodeOptions) -> Result<Self, QoiError> {
         let mut bytes = Vec::new();
         input_buf.read_to_end(&mut bytes)?;
-        let (bytes, header) = QOIHeader::parse(&bytes).map_err(|e| e.to_owned())?;
-        let image_data_len = (header.width * header.height) as usize * 4;
-        let (_, image_data) = parse_image_data(bytes, image_data_len).map_err(|e| e.to_owned())?;
+        Self::decode_slice_with(&bytes, options)
+    }
+
+    /// Like [`decode`](Self::decode), but calls `on_progress(pixels_decoded, total_pixels)`
+    /// roughly every million pixels and once more when decoding finishes.
+    #[cfg(feature = "std")]
+    pub fn decode_with_progress(
+        mut input_buf: impl Read,
+        on_progress: impl FnMut(u64, u64),
+    ) -> Result<Self, QoiError> {
+        let mut bytes = Vec::new();
+        input_buf.read_to_end(&mut bytes)?;
+        let mut image_data = Vec::new();
+        let header = decode_bytes(
+            &bytes,
+            &mut image_data,
+            DecodeOptions::default(),
+            false,
+            on_progress,
+            |_, _| {},
+        )?;
         Ok(Self { header, image_data })
     }
 
-    pub fn write_png_file(&self, out_file_buf: impl Write) -> Result<(), Box<dyn Error>> {
-        let mut encoder = png::Encoder::new(out_file_buf, self.header.width, self.header.height);
-        encoder.set_color(png::ColorType::Rgba);
-        let mut writer = encoder.write_header()?;
-        writer.write_image_data(&self.image_data)?;
-        Ok(())
+    /// Like [`decode`](Self::decode), but also counts the ops the file used.
+    #[cfg(feature = "std")]
+    pub fn decode_with_stats(mut input_buf: impl Read) -> Result<(Self, OpStats), QoiError> {
+        let mut bytes = Vec::new();
+        input_buf.read_to_end(&mut bytes)?;
+        Self::decode_slice_with_stats(&bytes)
+    }
+
+    pub fn decode_slice_with_stats(bytes: &[u8]) -> Result<(Self, OpStats), QoiError> {
+        let mut image_data = Vec::new();
+        let mut stats = OpStats::default();
+        let header = decode_bytes(
+            bytes,
+            &mut image_data,
+            DecodeOptions::default(),
+            false,
+            |_, _| {},
+            |op_code, pixels| stats.record(op_code, pixels),
+        )?;
+        Ok((Self { header, image_data }, stats))
+    }
+
+    /// Decodes leniently (see [`DecodeOptions::strict`]), reporting how many pixels were
+    /// actually recovered so a truncated file can still be shown as a partial preview. Only
+    /// a bad header is an error.
+    pub fn decode_partial(bytes: &[u8]) -> Result<PartialImage, QoiError> {
+        let mut image_data = Vec::new();
+        let mut decoded_pixels = 0;
+        let header = decode_bytes(
+            bytes,
+            &mut image_data,
+            DecodeOptions::default().strict(false),
+            false,
+            |_, _| {},
+            |_, pixels| decoded_pixels += pixels as u64,
+        )?;
+        Ok(PartialImage {
+            image: Self { header, image_data },
+            decoded_pixels,
+        })
+    }
+
+    /// Decodes leniently (see [`DecodeOptions::strict`]), collecting what was wrong with the
+    /// file instead of failing on it. Only a bad header is an error.
+    #[cfg(feature = "std")]
+    pub fn decode_with_warnings(
+        mut input_buf: impl Read,
+    ) -> Result<(Self, Vec<DecodeWarning>), QoiError> {
+        let mut bytes = Vec::new();
+        input_buf.read_to_end(&mut bytes)?;
+        Self::decode_slice_with_warnings(&bytes)
+    }
+
+    pub fn decode_slice_with_warnings(
+        bytes: &[u8],
+    ) -> Result<(Self, Vec<DecodeWarning>), QoiError> {
+        Self::decode_slice_with_warnings_with(bytes, DecodeOptions::default())
+    }
+
+    /// Like [`decode_slice_with_warnings`](Self::decode_slice_with_warnings), with the other
+    /// options taken from `options`; `strict` is ignored. Without `require_end_marker`
+    /// nothing after the pixels is looked at, so neither marker warning comes up, and the
+    /// same goes for a file cut short by an unexpected index op.
+    pub fn decode_slice_with_warnings_with(
+        bytes: &[u8],
+        options: DecodeOptions,
+    ) -> Result<(Self, Vec<DecodeWarning>), QoiError> {
+        let mut image_data = Vec::new();
+        let mut warnings = Vec::new();
+        let mut stopped_early = false;
+        let (header, rest) = decode_prefix(
+            bytes,
+            &mut image_data,
+            options.strict(false),
+            false,
+            |_, _| {},
+            |_, _| {},
+            |warning| {
+                stopped_early |= matches!(warning, DecodeWarning::UnexpectedIndexOp { .. });
+                warnings.push(warning)
+            },
+        )?;
+        // After an unexpected index op `rest` points into the ops, not at the end marker.
+        if options.require_end_marker && !stopped_early {
+            match skip_end_marker(rest, bytes.len() - rest.len()) {
+                Ok([]) => {}
+                Ok(trailing) => warnings.push(DecodeWarning::TrailingBytes(trailing.len())),
+                Err(_) => warnings.push(DecodeWarning::MissingEndMarker),
+            }
+        }
+        Ok((Self { header, image_data }, warnings))
+    }
+
+    /// Reads and validates only the header, without decoding any pixels.
+    #[cfg(feature = "std")]
+    pub fn read_header(mut input_buf: impl Read) -> Result<QOIHeader, QoiError> {
+        let mut bytes = [0; HEADER_LEN];
+        read_exact(&mut input_buf, &mut bytes, 0)?;
+        let (_, header) =
+            QOIHeader::parse(&bytes).map_err(|e| QoiError::from_nom(e, 0, QoiError::BadMagic))?;
+        header.validate()?;
+        Ok(header)
+    }
+
+    /// Parses the header from the reader's buffer without consuming anything, so the same
+    /// reader can still be handed to a decoder afterwards. Only one `fill_buf` call is made,
+    /// so the whole 14-byte header has to arrive in it. A `BufReader` over a file or a slice
+    /// manages that, but a pipe or socket may hand over fewer bytes at first, and so may a
+    /// reader with a tiny buffer. A partial header is [`QoiError::HeaderNotBuffered`],
+    /// since there's no telling from here whether more is coming, and `UnexpectedEof` is
+    /// left for an input that's already at its end. Fall back on
+    /// [`read_header`](Self::read_header) when peeking isn't possible.
+    #[cfg(feature = "std")]
+    pub fn peek_header(reader: &mut impl BufRead) -> Result<QOIHeader, QoiError> {
+        let bytes = reader.fill_buf()?;
+        if bytes.is_empty() {
+            return Err(QoiError::UnexpectedEof { offset: 0 });
+        }
+        // What little arrived can already rule out the magic; otherwise wait for the rest.
+        if bytes.len() < HEADER_LEN && b"qoif".starts_with(&bytes[..bytes.len().min(4)]) {
+            return Err(QoiError::HeaderNotBuffered {
+                buffered: bytes.len(),
+            });
+        }
+        let (_, header) =
+            QOIHeader::parse(bytes).map_err(|e| QoiError::from_nom(e, 0, QoiError::BadMagic))?;
+        header.validate()?;
+        Ok(header)
+    }
+
+    /// Decodes a complete QOI file held in memory. A header with a zero width or height is
+    /// accepted as an empty image: no ops are read, only the end marker, and the pixel buffer
+    /// is empty.
+    pub fn decode_slice(bytes: &[u8]) -> Result<Self, QoiError> {
+        Self::decode_slice_with(bytes, DecodeOptions::default())
+    }
+
+    pub fn decode_slice_with(bytes: &[u8], options: DecodeOptions) -> Result<Self, QoiError> {
+        let mut image_data = Vec::new();
+        let header = Self::decode_into_with(bytes, &mut image_data, options)?;
+        Ok(Self { header, image_data })
+    }
+
+    /// Decodes a QOI file at the start of `bytes`, ignoring whatever follows its end marker,
+    /// and returns the image along with the file's length: the offset at which the rest of
+    /// the stream starts.
+    pub fn decode_slice_with_len(bytes: &[u8]) -> Result<(Self, usize), QoiError> {
+        let mut image_data = Vec::new();
+        let (header, rest) = decode_prefix(
+            bytes,
+            &mut image_data,
+            DecodeOptions::default(),
+            false,
+            |_, _| {},
+            |_, _| {},
+            |_| {},
+        )?;
+        Ok((Self { header, image_data }, bytes.len() - rest.len()))
+    }
+
+    /// Decodes into `image_data`, reusing its allocation, and returns just the header.
+    /// The buffer is cleared first and holds RGBA8 pixels on success.
+    pub fn decode_into(bytes: &[u8], image_data: &mut Vec<u8>) -> Result<QOIHeader, QoiError> {
+        Self::decode_into_with(bytes, image_data, DecodeOptions::default())
+    }
+
+    pub fn decode_into_with(
+        bytes: &[u8],
+        image_data: &mut Vec<u8>,
+        options: DecodeOptions,
+    ) -> Result<QOIHeader, QoiError> {
+        decode_bytes(bytes, image_data, options, false, |_, _| {}, |_, _| {})
+    }
+
+    pub fn header(&self) -> &QOIHeader {
+        &self.header
+    }
+
+    pub fn width(&self) -> u32 {
+        self.header.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.header.height
+    }
+
+    pub fn dimensions(&self) -> (u32, u32) {
+        (self.header.width, self.header.height)
+    }
+
+    pub fn channels(&self) -> u8 {
+        self.header.channels
+    }
+
+    pub fn colorspace(&self) -> u8 {
+        self.header.colorspace
+    }
+
+    /// Decoded pixels as tightly packed, row-major RGBA8.
+    pub fn as_rgba_bytes(&self) -> &[u8] {
+        &self.image_data
+    }
+
+    /// Takes ownership of the tightly packed, row-major RGBA8 pixel buffer.
+    pub fn into_rgba_bytes(self) -> Vec<u8> {
+        self.image_data
+    }
+
+    #[cfg(feature = "std")]
+    fn to_rgb_bytes(&self) -> Vec<u8> {
+        self.image_data
+            .chunks_exact(4)
+            .flat_map(|chunk| &chunk[..3])
+            .copied()
+            .collect()
+    }
+
+    pub fn pixels(&self) -> impl Iterator<Item = Pixel> + '_ {
+        self.image_data.chunks_exact(4).map(Pixel::from_slice)
+    }
+
+    /// Yields each scanline top to bottom as `width * 4` bytes of RGBA8.
+    pub fn rows(&self) -> impl Iterator<Item = &[u8]> + '_ {
+        let stride = self.header.width as usize * 4;
+        (0..self.header.height as usize)
+            .map(move |y| &self.image_data[y * stride..(y + 1) * stride])
+    }
+
+    pub fn get_pixel(&self, x: u32, y: u32) -> Option<Pixel> {
+        if x >= self.header.width || y >= self.header.height {
+            return None;
+        }
+        let offset = (y as usize * self.header.width as usize + x as usize) * 4;
+        Some(Pixel::from_slice(&self.image_data[offset..offset + 4]))
+    }
+
+    /// Like [`pixels`](Self::pixels), but also yields the `(x, y)` coordinate of each pixel.
+    pub fn enumerate_pixels(&self) -> impl Iterator<Item = (u32, u32, Pixel)> + '_ {
+        let width = self.header.width as usize;
+        self.pixels()
+            .enumerate()
+            .map(move |(i, pixel)| ((i % width) as u32, (i / width) as u32, pixel))
+    }
+
+    #[cfg(feature = "std")]
+    pub fn encode(&self, out_buf: impl Write) -> Result<(), QoiError> {
+        self.encode_with(out_buf, EncodeOptions::default())
+    }
+
+    #[cfg(feature = "std")]
+    pub fn encode_with(&self, out_buf: impl Write, options: EncodeOptions) -> Result<(), QoiError> {
+        self.write_with(&QoiEncoder(options), out_buf)
+    }
+
+    pub fn to_qoi_bytes(&self) -> Vec<u8> {
+        self.to_qoi_bytes_with(EncodeOptions::default())
+    }
+
+    pub fn to_qoi_bytes_with(&self, options: EncodeOptions) -> Vec<u8> {
+        let colorspace = options
+            .colorspace
+            .map_or(self.header.colorspace, |colorspace| colorspace as u8);
+        let mut bytes = Vec::new();
+        QOIHeader::new(
+            self.header.width,
+            self.header.height,
+            self.header.channels,
+            colorspace,
+        )
+        .write(&mut bytes);
+        encode_image_data(&self.image_data, options, |op| bytes.extend_from_slice(op));
+        bytes.extend_from_slice(&END_MARKER);
+        bytes
+    }
+
+    /// The length in bytes of [`to_qoi_bytes`](Self::to_qoi_bytes)'s output, computed by
+    /// running the encoder without writing anything.
+    pub fn encoded_size(&self) -> usize {
+        self.encoded_size_with(EncodeOptions::default())
+    }
+
+    pub fn encoded_size_with(&self, options: EncodeOptions) -> usize {
+        let mut len = HEADER_LEN + END_MARKER.len();
+        encode_image_data(&self.image_data, options, |op| len += op.len());
+        len
+    }
+}
+
+/// Swaps the first and third byte of every 4-byte pixel. Treating each pixel as one word
+/// turns the swap into a fixed mask-and-shift that the compiler vectorizes.
+#[cfg(feature = "std")]
+pub(crate) fn swap_rb_inplace(bytes: &mut [u8]) {
+    for chunk in bytes.chunks_exact_mut(4) {
+        let pixel = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        let swapped = (pixel & 0xff00ff00) | (pixel & 0xff) << 16 | (pixel >> 16) & 0xff;
+        chunk.copy_from_slice(&swapped.to_le_bytes());
+    }
+}
+
+/// Fills `bytes`, which hold whatever starts at byte `offset` of the file, reporting a
+/// short read as [`QoiError::UnexpectedEof`] at that offset.
+#[cfg(feature = "std")]
+fn read_exact(input_buf: &mut impl Read, bytes: &mut [u8], offset: usize) -> Result<(), QoiError> {
+    input_buf.read_exact(bytes).map_err(|e| match e.kind() {
+        io::ErrorKind::UnexpectedEof => QoiError::UnexpectedEof { offset },
+        _ => e.into(),
+    })
+}
+
+/// Decodes into RGBA8, or into packed RGB8 for three-channel images when `pack_rgb` is set.
+fn decode_bytes(
+    bytes: &[u8],
+    image_data: &mut Vec<u8>,
+    options: DecodeOptions,
+    pack_rgb: bool,
+    on_progress: impl FnMut(u64, u64),
+    on_op: impl FnMut(u8, usize),
+) -> Result<QOIHeader, QoiError> {
+    let whole_file = options.strict && options.require_end_marker;
+    let (header, rest) = decode_prefix(
+        bytes,
+        image_data,
+        options,
+        pack_rgb,
+        on_progress,
+        on_op,
+        |_| {},
+    )
+    .map_err(|e| {
+        if whole_file {
+            diagnose_pixel_count(bytes, e)
+        } else {
+            e
+        }
+    })?;
+    if whole_file {
+        reject_trailing_data(rest)?;
+    }
+    Ok(header)
+}
+
+/// A header that declares the wrong dimensions shows up as the ops running out early or
+/// carrying on past where the end marker should be. When `bytes` failed to decode like
+/// that but its ops do describe a complete image of some other size, this swaps `err` for
+/// [`QoiError::PixelCountMismatch`], which says what actually went wrong.
+fn diagnose_pixel_count(bytes: &[u8], err: QoiError) -> QoiError {
+    if !matches!(
+        err,
+        QoiError::UnexpectedEof { .. }
+            | QoiError::MissingEndMarker { .. }
+            | QoiError::TooManyPixels
+    ) {
+        return err;
+    }
+    let Ok((ops, header)) = QOIHeader::parse(bytes) else {
+        return err;
+    };
+    let expected = header.width as u64 * header.height as u64;
+    match count_pixels(ops) {
+        Some(got) if got != expected => QoiError::PixelCountMismatch { expected, got },
+        _ => err,
     }
 }
 
-fn skip_two_bits<'a, O>(
-    parser: impl Parser<(&'a [u8], usize), O, nom::error::Error<(&'a [u8], usize)>>,
-) -> impl FnMut(&'a [u8]) -> IResult<&[u8], O> {
-    bits(preceded::<_, u8, _, _, _, _>(take(2_usize), parser))
+/// Counts the pixels described by `ops`, provided they end exactly where an end marker
+/// closes the input.
+fn count_pixels(ops: &[u8]) -> Option<u64> {
+    let end = ops.len().checked_sub(END_MARKER.len())?;
+    if ops[end..] != END_MARKER {
+        return None;
+    }
+    let (mut pos, mut pixels) = (0, 0);
+    while pos < end {
+        pixels += op_pixels(ops[pos]);
+        pos += op_len(ops[pos]);
+    }
+    (pos == end).then_some(pixels)
 }
 
-fn parse_image_data(mut bytes: &[u8], image_data_len: usize) -> IResult<&[u8], Vec<u8>> {
-    let mut image_data = Vec::with_capacity(image_data_len);
+/// How many pixels the op starting with `op_code` produces.
+fn op_pixels(op_code: u8) -> u64 {
+    match op_code {
+        RGB | RGBA => 1,
+        RUN::START..=RUN::END => (op_code & 0b111111) as u64 + 1,
+        _ => 1,
+    }
+}
+
+/// The length in bytes of the op starting with `op_code`.
+fn op_len(op_code: u8) -> usize {
+    match op_code {
+        RGB => 4,
+        RGBA => 5,
+        LUMA::START..=LUMA::END => 2,
+        _ => 1,
+    }
+}
+
+/// Like [`decode_bytes`], but tolerates anything after the image and returns it. That's
+/// the input after the end marker, or after the last op when the marker isn't checked.
+fn decode_prefix<'a>(
+    bytes: &'a [u8],
+    image_data: &mut Vec<u8>,
+    options: DecodeOptions,
+    pack_rgb: bool,
+    on_progress: impl FnMut(u64, u64),
+    on_op: impl FnMut(u8, usize),
+    on_warning: impl FnMut(DecodeWarning),
+) -> Result<(QOIHeader, &'a [u8]), QoiError> {
+    #[cfg(feature = "tracing")]
+    let _span = tracing::debug_span!("decode", input_len = bytes.len()).entered();
+    #[cfg(feature = "tracing")]
+    let (input_len, start) = (bytes.len(), std::time::Instant::now());
+    let (bytes, header) =
+        QOIHeader::parse(bytes).map_err(|e| QoiError::from_nom(e, 0, QoiError::BadMagic))?;
+    header.validate()?;
+    let pixel_len = if pack_rgb && header.channels == 3 {
+        3
+    } else {
+        4
+    };
+    let image_data_len = header.image_data_len()? / 4 * pixel_len;
+    image_data.clear();
+    // No op byte yields more than one full run, so a tiny file claiming huge dimensions
+    // can't make us reserve the whole declared size up front.
+    image_data
+        .reserve(image_data_len.min(bytes.len().saturating_mul(MAX_RUN as usize * pixel_len)));
+    let rest = if pixel_len == 3 {
+        parse_image_data::<3>(
+            bytes,
+            image_data_len,
+            image_data,
+            options,
+            on_progress,
+            on_op,
+            on_warning,
+        )?
+    } else {
+        parse_image_data::<4>(
+            bytes,
+            image_data_len,
+            image_data,
+            options,
+            on_progress,
+            on_op,
+            on_warning,
+        )?
+    };
+    #[cfg(feature = "tracing")]
+    {
+        let elapsed = start.elapsed();
+        tracing::debug!(
+            pixels = image_data_len / pixel_len,
+            elapsed_us = elapsed.as_micros() as u64,
+            mb_per_s = input_len as f64 / elapsed.as_secs_f64().max(f64::EPSILON) / 1e6,
+            "decoded"
+        );
+    }
+    Ok((header, rest))
+}
+
+enum Op {
+    Pixel(Pixel),
+    Run(usize),
+}
+
+/// Parses the op starting at `bytes[pos]`, returning it along with the position just past
+/// it, or `None` if the input ends first.
+#[inline]
+fn parse_op(
+    bytes: &[u8],
+    pos: usize,
+    prev_pixel: Pixel,
+    color_index_array: &[Pixel; 64],
+) -> Option<(Op, usize)> {
+    let op_code = *bytes.get(pos)?;
+    let pixel = match op_code {
+        RGB => {
+            let [r, g, b]: [u8; 3] = bytes.get(pos + 1..pos + 4)?.try_into().ok()?;
+            return Some((Op::Pixel(Pixel::new(r, g, b, prev_pixel.a)), pos + 4));
+        }
+        RGBA => {
+            let [r, g, b, a]: [u8; 4] = bytes.get(pos + 1..pos + 5)?.try_into().ok()?;
+            return Some((Op::Pixel(Pixel::new(r, g, b, a)), pos + 5));
+        }
+        INDEX::START..=INDEX::END => color_index_array[(op_code & 0b111111) as usize],
+        DIFF::START..=DIFF::END => {
+            let diff = |shift: u8| ((op_code >> shift) & 0b11).wrapping_sub(2);
+            prev_pixel.wrapping_add(diff(4), diff(2), diff(0))
+        }
+        LUMA::START..=LUMA::END => {
+            let second = *bytes.get(pos + 1)?;
+            let dg = (op_code & 0b111111).wrapping_sub(32);
+            let dr = dg.wrapping_add((second >> 4).wrapping_sub(8));
+            let db = dg.wrapping_add((second & 0b1111).wrapping_sub(8));
+            return Some((Op::Pixel(prev_pixel.wrapping_add(dr, dg, db)), pos + 2));
+        }
+        // 0xfe and 0xff are taken by RGB and RGBA above, so the largest run byte is 0xfd and
+        // no run is longer than MAX_RUN. An encoder that meant 63 or 64 there wrote an RGB
+        // or RGBA op instead, which can't be told apart, so there's nothing to tolerate.
+        RUN::START..=RUN::END => {
+            return Some((Op::Run((op_code & 0b111111) as usize + 1), pos + 1));
+        }
+    };
+    Some((Op::Pixel(pixel), pos + 1))
+}
+
+/// Decodes pixels stored as their first `PIXEL_LEN` bytes of RGBA. `bytes` starts right
+/// after the header, which errors take into account when reporting offsets.
+fn parse_image_data<'a, const PIXEL_LEN: usize>(
+    bytes: &'a [u8],
+    image_data_len: usize,
+    image_data: &mut Vec<u8>,
+    options: DecodeOptions,
+    mut on_progress: impl FnMut(u64, u64),
+    mut on_op: impl FnMut(u8, usize),
+    mut on_warning: impl FnMut(DecodeWarning),
+) -> Result<&'a [u8], QoiError> {
     let mut color_index_array = [Pixel::new(0, 0, 0, 0); 64];
     let mut prev_pixel = Pixel::new(0, 0, 0, 255);
-    let n_bit_diff = |n: usize| map(take(n), move |diff: u8| diff.wrapping_sub(1 << (n - 1)));
+    let total_pixels = (image_data_len / PIXEL_LEN) as u64;
+    let mut next_progress = PROGRESS_INTERVAL * PIXEL_LEN;
+    let mut pos = 0;
     while image_data.len() < image_data_len {
-        let (bytes_without_op_code, op_code) = be_u8(bytes)?;
-        let (rest, pixel) = match op_code {
-            RGB => {
-                let parse_chunk = tuple((be_u8, be_u8, be_u8));
-                let to_pixel = |(r, g, b)| Pixel::new(r, g, b, prev_pixel.a);
-                map(parse_chunk, to_pixel)(bytes_without_op_code)?
+        if image_data.len() >= next_progress {
+            on_progress((image_data.len() / PIXEL_LEN) as u64, total_pixels);
+            next_progress += PROGRESS_INTERVAL * PIXEL_LEN;
+        }
+        let (op, next) = match parse_op(bytes, pos, prev_pixel, &color_index_array) {
+            Some(parsed) => parsed,
+            None if !options.strict => {
+                on_warning(DecodeWarning::PixelCountMismatch {
+                    expected: total_pixels,
+                    got: (image_data.len() / PIXEL_LEN) as u64,
+                });
+                image_data.resize(image_data_len, 0);
+                break;
             }
-            RGBA => {
-                let parse_chunk = tuple((be_u8, be_u8, be_u8, be_u8));
-                let to_pixel = |(r, g, b, a)| Pixel::new(r, g, b, a);
-                map(parse_chunk, to_pixel)(bytes_without_op_code)?
+            None => {
+                return Err(QoiError::UnexpectedEof {
+                    offset: HEADER_LEN + pos,
+                })
             }
-            INDEX::START..=INDEX::END => {
-                let parse_chunk = take(6_usize);
-                let to_pixel = |idx: usize| color_index_array[idx];
-                skip_two_bits(map(parse_chunk, to_pixel))(bytes)?
+        };
+        let op_code = bytes[pos];
+        pos = next;
+        match op {
+            Op::Pixel(pixel) => {
+                if let INDEX::START..=INDEX::END = op_code {
+                    if options.assume_no_index {
+                        if options.strict {
+                            return Err(QoiError::UnexpectedIndexOp);
+                        }
+                        // Index ops are a single byte, so this one started just before `pos`.
+                        on_warning(DecodeWarning::UnexpectedIndexOp {
+                            offset: HEADER_LEN + pos - 1,
+                        });
+                        image_data.resize(image_data_len, 0);
+                        break;
+                    }
+                    // A slot only ever holds its initial transparent black or a pixel that
+                    // hashes to it, so that's all an index op may resolve to.
+                    let idx = (op_code - INDEX::START) as usize;
+                    debug_assert_eq!(pixel, color_index_array[idx]);
+                    debug_assert!(
+                        pixel.hash() == idx || pixel == Pixel::new(0, 0, 0, 0),
+                        "index slot {} holds {:?}, which hashes to {}",
+                        idx,
+                        pixel,
+                        pixel.hash()
+                    );
+                }
+                on_op(op_code, 1);
+                image_data.extend_from_slice(&pixel.flat()[..PIXEL_LEN]);
+                if !options.assume_no_index {
+                    color_index_array[pixel.hash()] = pixel;
+                }
+                prev_pixel = pixel;
             }
-            DIFF::START..=DIFF::END => {
-                let parse_chunk = tuple((n_bit_diff(2), n_bit_diff(2), n_bit_diff(2)));
-                let to_pixel = |(dr, dg, db)| prev_pixel.wrapping_add(dr, dg, db);
-                skip_two_bits(map(parse_chunk, to_pixel))(bytes)?
+            Op::Run(mut run) => {
+                let remaining = (image_data_len - image_data.len()) / PIXEL_LEN;
+                if run > remaining {
+                    if options.strict {
+                        return Err(QoiError::TooManyPixels);
+                    }
+                    on_warning(DecodeWarning::PixelCountMismatch {
+                        expected: total_pixels,
+                        got: (image_data.len() / PIXEL_LEN + run) as u64,
+                    });
+                    run = remaining;
+                }
+                on_op(op_code, run);
+                let flat_pixel = prev_pixel.flat();
+                let start = image_data.len();
+                image_data.resize(start + run * PIXEL_LEN, 0);
+                image_data[start..]
+                    .chunks_exact_mut(PIXEL_LEN)
+                    .for_each(|chunk| chunk.copy_from_slice(&flat_pixel[..PIXEL_LEN]));
             }
-            LUMA::START..=LUMA::END => {
-                let parse_chunk = tuple((n_bit_diff(6), n_bit_diff(4), n_bit_diff(4)));
-                let to_pixel = |(dg, drdg, dbdg): (u8, u8, u8)| {
-                    let dr = dg.wrapping_add(drdg);
-                    let db = dg.wrapping_add(dbdg);
-                    prev_pixel.wrapping_add(dr, dg, db)
-                };
-                skip_two_bits(map(parse_chunk, to_pixel))(bytes)?
+        }
+    }
+    on_progress(total_pixels, total_pixels);
+    if !options.strict || !options.require_end_marker {
+        return Ok(&bytes[pos..]);
+    }
+    skip_end_marker(&bytes[pos..], HEADER_LEN + pos)
+}
+
+/// Skips the end marker, which is expected at byte `offset` of the file.
+fn skip_end_marker(bytes: &[u8], offset: usize) -> Result<&[u8], QoiError> {
+    let (bytes, _) = tag(END_MARKER)(bytes)
+        .map_err(|_: nom::Err<nom::error::Error<_>>| QoiError::MissingEndMarker { offset })?;
+    Ok(bytes)
+}
+
+fn reject_trailing_data(rest: &[u8]) -> Result<(), QoiError> {
+    if !rest.is_empty() {
+        return Err(QoiError::TrailingData(rest.len()));
+    }
+    Ok(())
+}
+
+/// Picks the op for each pixel and hands its encoded bytes to `emit`.
+fn encode_image_data(image_data: &[u8], options: EncodeOptions, mut emit: impl FnMut(&[u8])) {
+    let mut encoder = OpEncoder::new(options);
+    for chunk in image_data.chunks_exact(4) {
+        encoder.push(Pixel::from_slice(chunk), &mut emit);
+    }
+    encoder.finish(emit);
+}
+
+/// The encoder's state between pixels: the previous pixel, the color index array and the
+/// length of the run in progress.
+struct OpEncoder {
+    options: EncodeOptions,
+    max_run: u8,
+    color_index_array: [Pixel; 64],
+    prev_pixel: Pixel,
+    run: u8,
+}
+
+impl OpEncoder {
+    fn new(options: EncodeOptions) -> Self {
+        Self {
+            options,
+            max_run: options.max_run.clamp(1, MAX_RUN),
+            color_index_array: [Pixel::new(0, 0, 0, 0); 64],
+            prev_pixel: Pixel::new(0, 0, 0, 255),
+            run: 0,
+        }
+    }
+
+    /// Encodes the next pixel. A pixel that repeats the previous one only extends the
+    /// pending run, so it may not emit anything until later.
+    // Plain `#[inline]` leaves `to_qoi_bytes` around 8% slower.
+    #[inline(always)]
+    fn push(&mut self, pixel: Pixel, mut emit: impl FnMut(&[u8])) {
+        let prev_pixel = self.prev_pixel;
+        if pixel == prev_pixel {
+            self.run += 1;
+            if self.run == self.max_run {
+                emit(&[RUN::START | (self.run - 1)]);
+                self.run = 0;
             }
-            RUN::START..=RUN::END => {
-                let (rest, run) = skip_two_bits(map(take(6_usize), |v: usize| v + 1))(bytes)?;
-                let flat_pixel = prev_pixel.flat();
-                (0..run).for_each(|_| image_data.extend_from_slice(&flat_pixel));
-                bytes = rest;
-                continue;
+            return;
+        }
+        self.finish(&mut emit);
+        let idx = pixel.hash();
+        if self.options.use_index && self.color_index_array[idx] == pixel {
+            emit(&[INDEX::START | idx as u8]);
+        } else if self.options.force_rgba || pixel.a != prev_pixel.a {
+            emit(&[RGBA, pixel.r, pixel.g, pixel.b, pixel.a]);
+        } else {
+            let diff_in_range =
+                |diff: i8, n: u32| (-(1 << (n - 1))..(1 << (n - 1))).contains(&diff);
+            let n_bit_diff = |diff: i8, n: u32| diff.wrapping_add(1 << (n - 1)) as u8;
+            let (dr, dg, db) = pixel.wrapping_diff(&prev_pixel);
+            let (drdg, dbdg) = (dr.wrapping_sub(dg), db.wrapping_sub(dg));
+            if [dr, dg, db].iter().all(|&diff| diff_in_range(diff, 2)) {
+                emit(&[DIFF::START
+                    | n_bit_diff(dr, 2) << 4
+                    | n_bit_diff(dg, 2) << 2
+                    | n_bit_diff(db, 2)]);
+            } else if diff_in_range(dg, 6) && diff_in_range(drdg, 4) && diff_in_range(dbdg, 4) {
+                emit(&[
+                    LUMA::START | n_bit_diff(dg, 6),
+                    n_bit_diff(drdg, 4) << 4 | n_bit_diff(dbdg, 4),
+                ]);
+            } else {
+                emit(&[RGB, pixel.r, pixel.g, pixel.b]);
             }
-        };
-        bytes = rest;
-        image_data.extend_from_slice(&pixel.flat());
-        color_index_array[pixel.hash()] = pixel;
-        prev_pixel = pixel;
+        }
+        self.color_index_array[idx] = pixel;
+        self.prev_pixel = pixel;
+    }
+
+    /// Emits the pending run, if there is one. Called after the last pixel, and before
+    /// any pixel that breaks the run.
+    #[inline]
+    fn finish(&mut self, mut emit: impl FnMut(&[u8])) {
+        if self.run > 0 {
+            emit(&[RUN::START | (self.run - 1)]);
+            self.run = 0;
+        }
     }
-    let (bytes, _) = tag(END_MARKER)(bytes)?;
-    Ok((bytes, image_data))
 }
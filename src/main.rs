@@ -1,21 +1,361 @@
 use clap::Parser;
-use std::{error::Error, fs::File, path::PathBuf};
+use qoi_decoder::{
+    BmpEncoder, Colorspace, EncodeOptions, ImageEncoder, PngEncoder, PngOptions, PpmEncoder,
+    QoiEncoder, TgaEncoder,
+};
+use std::{
+    collections::HashMap,
+    error::Error,
+    fs::{self, File},
+    io::{self, Read, Write},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+    thread,
+};
 /// A Quite Ok Image format decoder.
 #[derive(Parser, Debug)]
 #[clap(author, version, about, long_about = None)]
 struct Cli {
-    /// file to decode
-    input: PathBuf,
+    /// file to decode followed by the output path, either of which may be "-" for
+    /// stdin/stdout; with --output-dir, --info or --verify, any number of input files
+    #[clap(required = true)]
+    paths: Vec<PathBuf>,
 
-    /// output path
-    output: PathBuf,
+    /// convert every input into this directory, keeping each file's stem; the directory is
+    /// created if it doesn't exist
+    #[clap(long, value_name = "DIR")]
+    output_dir: Option<PathBuf>,
+
+    /// number of files to convert concurrently with --output-dir
+    #[clap(long, short, default_value_t = 1)]
+    jobs: usize,
+
+    /// print the header metadata instead of converting
+    #[clap(long)]
+    info: bool,
+
+    /// fully decode each input and report OK or the error, without writing anything
+    #[clap(long, conflicts_with = "info")]
+    verify: bool,
+
+    /// encode a PNG input to QOI; same as --format qoi, but also reads the input as PNG
+    /// whatever its extension
+    #[clap(long, conflicts_with_all = &["to-png", "format"])]
+    to_qoi: bool,
+
+    /// decode a QOI input to PNG; same as --format png, but also reads the input as QOI
+    /// whatever its extension
+    #[clap(long, conflicts_with = "format")]
+    to_png: bool,
+
+    /// output format, instead of going by the output file's extension; inputs ending in
+    /// .png are read as PNG and everything else as QOI
+    #[clap(long, arg_enum)]
+    format: Option<OutputFormat>,
+
+    /// memory-map the QOI input instead of reading it into memory
+    #[clap(long)]
+    mmap: bool,
+
+    /// colorspace to declare in the output instead of the input's: srgb (QOI colorspace 0,
+    /// linear alpha) or linear (QOI colorspace 1)
+    #[clap(long, arg_enum)]
+    colorspace: Option<ColorspaceArg>,
+}
+
+#[derive(clap::ArgEnum, Clone, Copy, Debug)]
+enum ColorspaceArg {
+    Srgb,
+    Linear,
+}
+
+impl From<ColorspaceArg> for Colorspace {
+    fn from(colorspace: ColorspaceArg) -> Self {
+        match colorspace {
+            ColorspaceArg::Srgb => Self::Srgb,
+            ColorspaceArg::Linear => Self::Linear,
+        }
+    }
+}
+
+#[derive(clap::ArgEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum OutputFormat {
+    Qoi,
+    Png,
+    Ppm,
+    Bmp,
+    Tga,
+}
+
+impl OutputFormat {
+    /// Picks the format from the path's extension, ignoring case.
+    fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "qoi" => Some(Self::Qoi),
+            "png" => Some(Self::Png),
+            "ppm" | "pam" => Some(Self::Ppm),
+            "bmp" => Some(Self::Bmp),
+            "tga" => Some(Self::Tga),
+            _ => None,
+        }
+    }
+
+    fn extension(self) -> &'static str {
+        match self {
+            Self::Qoi => "qoi",
+            Self::Png => "png",
+            Self::Ppm => "ppm",
+            Self::Bmp => "bmp",
+            Self::Tga => "tga",
+        }
+    }
+
+    /// The encoder for this format. Only QOI and PNG record a colorspace, so the others
+    /// ignore `colorspace`.
+    fn encoder(self, colorspace: Option<Colorspace>) -> Box<dyn ImageEncoder> {
+        match self {
+            Self::Qoi => Box::new(QoiEncoder(EncodeOptions::default().colorspace(colorspace))),
+            Self::Png => Box::new(PngEncoder(PngOptions {
+                colorspace,
+                ..PngOptions::default()
+            })),
+            Self::Ppm => Box::new(PpmEncoder),
+            Self::Bmp => Box::new(BmpEncoder),
+            Self::Tga => Box::new(TgaEncoder),
+        }
+    }
+}
+
+impl Cli {
+    fn png_input(&self, input: &Path) -> bool {
+        self.to_qoi || (!self.to_png && input.extension() == Some("png".as_ref()))
+    }
+
+    /// The format given on the command line, if any.
+    fn explicit_format(&self) -> Option<OutputFormat> {
+        match (self.format, self.to_qoi, self.to_png) {
+            (Some(format), _, _) => Some(format),
+            (None, true, _) => Some(OutputFormat::Qoi),
+            (None, _, true) => Some(OutputFormat::Png),
+            (None, false, false) => None,
+        }
+    }
+
+    /// The format given on the command line, otherwise the one the output's extension names.
+    /// Writing to stdout without a format keeps the old behavior of converting PNG to QOI
+    /// and anything else to PNG.
+    fn output_format(&self, input: &Path, output: &Path) -> Result<OutputFormat, Box<dyn Error>> {
+        if let Some(format) = self.explicit_format() {
+            return Ok(format);
+        }
+        if output == Path::new("-") {
+            return Ok(self.default_format(input));
+        }
+        OutputFormat::from_path(output).ok_or_else(|| {
+            format!(
+                "can't tell the output format of {} from its extension; pass --format",
+                output.display()
+            )
+            .into()
+        })
+    }
+
+    fn default_format(&self, input: &Path) -> OutputFormat {
+        if self.png_input(input) {
+            OutputFormat::Qoi
+        } else {
+            OutputFormat::Png
+        }
+    }
+}
+
+fn open_input(path: &Path) -> io::Result<Box<dyn Read>> {
+    if path == Path::new("-") {
+        Ok(Box::new(io::stdin().lock()))
+    } else {
+        Ok(Box::new(File::open(path)?))
+    }
+}
+
+/// Decodes straight from a memory map of the file, or returns `None` if it can't be mapped.
+fn decode_mmap(path: &Path) -> Option<Result<qoi_decoder::ImageData, qoi_decoder::QoiError>> {
+    if path == Path::new("-") {
+        return None;
+    }
+    let file = File::open(path).ok()?;
+    // SAFETY: the map is only read while decoding, and the file must not be modified by
+    // another process in the meantime; a concurrent write could change bytes mid-decode.
+    let map = unsafe { memmap2::Mmap::map(&file) }.ok()?;
+    Some(qoi_decoder::ImageData::decode_slice(&map))
+}
+
+fn create_output(path: &Path) -> io::Result<Box<dyn Write>> {
+    if path == Path::new("-") {
+        Ok(Box::new(io::stdout().lock()))
+    } else {
+        Ok(Box::new(File::create(path)?))
+    }
+}
+
+fn print_info(path: &Path) -> Result<(), Box<dyn Error>> {
+    let header = qoi_decoder::ImageData::read_header(open_input(path)?)?;
+    let colorspace = match header.colorspace() {
+        0 => "sRGB with linear alpha",
+        _ => "all channels linear",
+    };
+    println!(
+        "{}: {}x{}, {} channels, {}",
+        path.display(),
+        header.width(),
+        header.height(),
+        header.channels(),
+        colorspace
+    );
+    Ok(())
+}
+
+fn decode(args: &Cli, input: &Path) -> Result<qoi_decoder::ImageData, Box<dyn Error>> {
+    match args.mmap.then(|| decode_mmap(input)).flatten() {
+        Some(image_data) => Ok(image_data?),
+        None => Ok(qoi_decoder::ImageData::decode(open_input(input)?)?),
+    }
+}
+
+/// Decodes every input, printing `OK` or the error for each.
+fn verify_all(args: &Cli) -> Result<(), Box<dyn Error>> {
+    let mut failed = 0;
+    for input in &args.paths {
+        match decode(args, input) {
+            Ok(_) => println!("{}: OK", input.display()),
+            Err(e) => {
+                println!("{}: {}", input.display(), e);
+                failed += 1;
+            }
+        }
+    }
+    if failed > 0 {
+        return Err(format!(
+            "{} of {} file(s) failed to decode",
+            failed,
+            args.paths.len()
+        )
+        .into());
+    }
+    Ok(())
+}
+
+fn convert(
+    args: &Cli,
+    input: &Path,
+    output: &Path,
+    format: OutputFormat,
+) -> Result<(), Box<dyn Error>> {
+    let image_data = if args.png_input(input) {
+        qoi_decoder::ImageData::read_png_file(open_input(input)?)?
+    } else {
+        decode(args, input)?
+    };
+    let encoder = format.encoder(args.colorspace.map(Colorspace::from));
+    let mut out_writer = create_output(output)?;
+    image_data.write_with(&*encoder, &mut out_writer)?;
+    out_writer.flush()?;
+    Ok(())
+}
+
+/// Converts every input into `output_dir` on `--jobs` threads, carrying on past individual
+/// failures and reporting every file once the batch is done. An input whose output file
+/// another input already claimed fails rather than overwriting it.
+fn convert_all(args: &Cli, output_dir: &Path) -> Result<(), Box<dyn Error>> {
+    fs::create_dir_all(output_dir).map_err(|e| {
+        format!(
+            "can't create output directory {}: {}",
+            output_dir.display(),
+            e
+        )
+    })?;
+    let outputs: Vec<(PathBuf, OutputFormat)> = args
+        .paths
+        .iter()
+        .map(|input| {
+            let format = args
+                .explicit_format()
+                .unwrap_or_else(|| args.default_format(input));
+            let mut file_name = input.file_stem().unwrap_or_default().to_owned();
+            file_name.push(".");
+            file_name.push(format.extension());
+            (output_dir.join(file_name), format)
+        })
+        .collect();
+    // Inputs sharing a stem would write the same file; only the first of them gets to.
+    let mut first_writer = HashMap::new();
+    for (i, (output, _)) in outputs.iter().enumerate() {
+        first_writer.entry(output).or_insert(i);
+    }
+    let next = AtomicUsize::new(0);
+    let results = Mutex::new(Vec::with_capacity(args.paths.len()));
+    thread::scope(|scope| {
+        for _ in 0..args.jobs.max(1) {
+            scope.spawn(|| loop {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                let Some(input) = args.paths.get(i) else {
+                    break;
+                };
+                let (output, format) = &outputs[i];
+                let first = first_writer[output];
+                let result = if first != i {
+                    Err(format!(
+                        "{} is already the output of {}",
+                        output.display(),
+                        args.paths[first].display()
+                    ))
+                } else {
+                    convert(args, input, output, *format)
+                        .map(|()| output.clone())
+                        .map_err(|e| e.to_string())
+                };
+                results.lock().unwrap().push((i, input, result));
+            });
+        }
+    });
+    let mut results = results.into_inner().unwrap();
+    results.sort_by_key(|&(i, _, _)| i);
+    let mut failed = 0;
+    for (_, input, result) in results {
+        match result {
+            Ok(output) => eprintln!("{} -> {}", input.display(), output.display()),
+            Err(e) => {
+                eprintln!("{}: {}", input.display(), e);
+                failed += 1;
+            }
+        }
+    }
+    eprintln!("{} converted, {} failed", args.paths.len() - failed, failed);
+    if failed > 0 {
+        return Err(format!("failed to convert {} file(s)", failed).into());
+    }
+    Ok(())
 }
 
 fn main() -> Result<(), Box<dyn Error>> {
     let args = Cli::parse();
-    let input_reader = File::open(args.input)?;
-    let image_data = qoi_decoder::ImageData::decode(input_reader)?;
-    let out_writer = File::create(args.output)?;
-    image_data.write_png_file(out_writer)?;
-    Ok(())
+    if args.info {
+        return args.paths.iter().try_for_each(|path| print_info(path));
+    }
+    if args.verify {
+        return verify_all(&args);
+    }
+    if let Some(output_dir) = &args.output_dir {
+        return convert_all(&args, output_dir);
+    }
+    match &args.paths[..] {
+        [input, output] => {
+            let format = args.output_format(input, output)?;
+            convert(&args, input, output, format)
+        }
+        _ => Err("expected an input and an output path, or --output-dir".into()),
+    }
 }
@@ -0,0 +1,52 @@
+use alloc::vec::Vec;
+
+use crate::{decode_bytes, DecodeOptions, ImageData, QOIHeader, QoiError};
+
+/// A decoded image stored with only the channels its header declares: tightly packed RGB8
+/// for three-channel files, RGBA8 otherwise.
+pub struct PackedImage {
+    header: QOIHeader,
+    image_data: Vec<u8>,
+}
+
+impl PackedImage {
+    pub fn header(&self) -> &QOIHeader {
+        &self.header
+    }
+
+    pub fn dimensions(&self) -> (u32, u32) {
+        (self.header.width, self.header.height)
+    }
+
+    /// The packed RGB8 pixels, or `None` for a four-channel image.
+    pub fn as_rgb_bytes(&self) -> Option<&[u8]> {
+        (self.header.channels == 3).then_some(&self.image_data[..])
+    }
+
+    /// The RGBA8 pixels, or `None` for a three-channel image.
+    pub fn as_rgba_bytes(&self) -> Option<&[u8]> {
+        (self.header.channels == 4).then_some(&self.image_data[..])
+    }
+
+    /// Takes ownership of the pixel buffer, `channels` bytes per pixel.
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.image_data
+    }
+}
+
+impl ImageData {
+    /// Like [`decode_slice`](Self::decode_slice), but three-channel images skip the alpha
+    /// byte entirely, saving a quarter of the memory.
+    pub fn decode_packed(bytes: &[u8]) -> Result<PackedImage, QoiError> {
+        let mut image_data = Vec::new();
+        let header = decode_bytes(
+            bytes,
+            &mut image_data,
+            DecodeOptions::default(),
+            true,
+            |_, _| {},
+            |_, _| {},
+        )?;
+        Ok(PackedImage { header, image_data })
+    }
+}
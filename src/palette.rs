@@ -0,0 +1,111 @@
+use alloc::{vec, vec::Vec};
+
+use crate::{ImageData, Pixel};
+
+/// A distinct color and how many pixels have it.
+type ColorCount = ([u8; 4], u64);
+
+impl ImageData {
+    /// Reduces the image to at most `max_colors` colors (clamped to 1..=256) with median
+    /// cut, returning the palette and each pixel's index into it in row-major order. An
+    /// image with few enough distinct colors keeps them exactly. Alpha is treated as a
+    /// fourth channel.
+    pub fn quantize(&self, max_colors: usize) -> (Vec<Pixel>, Vec<u8>) {
+        let max_colors = max_colors.clamp(1, 256);
+        let mut colors: Vec<[u8; 4]> = self.image_data.chunks_exact(4).map(flat).collect();
+        colors.sort_unstable();
+        let mut counts: Vec<ColorCount> = Vec::new();
+        for color in colors {
+            match counts.last_mut() {
+                Some((last, count)) if *last == color => *count += 1,
+                _ => counts.push((color, 1)),
+            }
+        }
+
+        if counts.is_empty() {
+            return (Vec::new(), Vec::new());
+        }
+
+        // Repeatedly split the box spanning the widest channel range at its weighted median.
+        // Each box keeps its widest channel alongside, so only split boxes are rescanned.
+        let mut boxes = vec![(widest_channel(&counts), counts)];
+        while boxes.len() < max_colors {
+            let widest = boxes
+                .iter()
+                .enumerate()
+                .filter(|(_, (_, colors))| colors.len() > 1)
+                .max_by_key(|(_, ((_, range), _))| *range);
+            let Some((i, _)) = widest else {
+                break;
+            };
+            let ((channel, _), colors) = &mut boxes[i];
+            let upper = split_at_median(colors, *channel);
+            boxes[i].0 = widest_channel(&boxes[i].1);
+            boxes.push((widest_channel(&upper), upper));
+        }
+
+        let mut lookup: Vec<([u8; 4], u8)> = Vec::new();
+        let mut palette = Vec::with_capacity(boxes.len());
+        for (i, (_, colors)) in boxes.iter().enumerate() {
+            palette.push(average(colors));
+            lookup.extend(colors.iter().map(|&(color, _)| (color, i as u8)));
+        }
+        lookup.sort_unstable();
+        let indices = self
+            .image_data
+            .chunks_exact(4)
+            .map(|chunk| {
+                let color = flat(chunk);
+                let at = lookup.binary_search_by_key(&color, |&(color, _)| color);
+                lookup[at.expect("every color is in some box")].1
+            })
+            .collect();
+        (palette, indices)
+    }
+}
+
+fn flat(chunk: &[u8]) -> [u8; 4] {
+    [chunk[0], chunk[1], chunk[2], chunk[3]]
+}
+
+/// The channel with the largest spread of values in `colors`, and that spread.
+fn widest_channel(colors: &[ColorCount]) -> (usize, u8) {
+    (0..4)
+        .map(|c| {
+            let values = colors.iter().map(|(color, _)| color[c]);
+            let (min, max) =
+                values.fold((u8::MAX, u8::MIN), |(min, max), v| (min.min(v), max.max(v)));
+            (c, max - min)
+        })
+        .max_by_key(|&(_, range)| range)
+        .unwrap_or((0, 0))
+}
+
+/// Sorts `colors` along `channel` and splits off the upper half by pixel count, leaving at
+/// least one color on each side.
+fn split_at_median(colors: &mut Vec<ColorCount>, channel: usize) -> Vec<ColorCount> {
+    colors.sort_unstable_by_key(|(color, _)| color[channel]);
+    let total: u64 = colors.iter().map(|(_, count)| count).sum();
+    let mut seen = 0;
+    let median = colors
+        .iter()
+        .position(|(_, count)| {
+            seen += count;
+            seen * 2 >= total
+        })
+        .unwrap_or(0);
+    colors.split_off((median + 1).min(colors.len() - 1))
+}
+
+/// The pixel-count-weighted mean color, rounded.
+fn average(colors: &[ColorCount]) -> Pixel {
+    let total: u64 = colors.iter().map(|(_, count)| count).sum();
+    let mean = |c: usize| {
+        let sum: u64 = colors
+            .iter()
+            .map(|(color, count)| color[c] as u64 * count)
+            .sum();
+        ((sum + total / 2) / total) as u8
+    };
+    Pixel::rgba(mean(0), mean(1), mean(2), mean(3))
+}
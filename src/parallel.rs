@@ -0,0 +1,126 @@
+use rayon::prelude::*;
+
+use crate::{
+    diagnose_pixel_count, parse_op, reject_trailing_data, skip_end_marker, ImageData, Op, Pixel,
+    QOIHeader, QoiError, HEADER_LEN,
+};
+
+const CHECKPOINT_INTERVAL: usize = 1 << 16;
+
+/// Decoder state at an op boundary, enough to resume decoding from there.
+struct Checkpoint {
+    byte_offset: usize,
+    pixel_offset: usize,
+    prev_pixel: Pixel,
+    color_index_array: [Pixel; 64],
+}
+
+impl ImageData {
+    /// Decodes on the rayon thread pool. A first pass walks the ops without writing pixels,
+    /// recording the decoder state every 65536 or so pixels; the segments between those
+    /// checkpoints are then decoded concurrently. The result and errors match
+    /// [`decode_slice`](Self::decode_slice).
+    pub fn decode_parallel(bytes: &[u8]) -> Result<Self, QoiError> {
+        let file = bytes;
+        let (bytes, header) =
+            QOIHeader::parse(bytes).map_err(|e| QoiError::from_nom(e, 0, QoiError::BadMagic))?;
+        header.validate()?;
+        let image_data_len = header.image_data_len()?;
+        let checkpoints =
+            scan_checkpoints(bytes, image_data_len).map_err(|e| diagnose_pixel_count(file, e))?;
+        let mut image_data = vec![0; image_data_len];
+        let mut segments = Vec::with_capacity(checkpoints.len());
+        let mut rest = image_data.as_mut_slice();
+        for (i, checkpoint) in checkpoints.iter().enumerate() {
+            let end = checkpoints
+                .get(i + 1)
+                .map_or(image_data_len, |next| next.pixel_offset);
+            let (segment, tail) = rest.split_at_mut(end - checkpoint.pixel_offset);
+            segments.push((checkpoint, segment));
+            rest = tail;
+        }
+        segments
+            .into_par_iter()
+            .try_for_each(|(checkpoint, segment)| decode_segment(bytes, checkpoint, segment))?;
+        Ok(Self { header, image_data })
+    }
+}
+
+/// Validates the whole op stream like the serial decoder, returning a checkpoint at the
+/// start and at the first op boundary past every `CHECKPOINT_INTERVAL` pixels.
+fn scan_checkpoints(bytes: &[u8], image_data_len: usize) -> Result<Vec<Checkpoint>, QoiError> {
+    let mut checkpoints = Vec::new();
+    let mut color_index_array = [Pixel::new(0, 0, 0, 0); 64];
+    let mut prev_pixel = Pixel::new(0, 0, 0, 255);
+    let mut pos = 0;
+    let mut pixel_offset = 0;
+    let mut next_checkpoint = 0;
+    while pixel_offset < image_data_len {
+        if pixel_offset >= next_checkpoint {
+            checkpoints.push(Checkpoint {
+                byte_offset: pos,
+                pixel_offset,
+                prev_pixel,
+                color_index_array,
+            });
+            next_checkpoint += CHECKPOINT_INTERVAL * 4;
+        }
+        let (op, next) = parse_op(bytes, pos, prev_pixel, &color_index_array).ok_or(
+            QoiError::UnexpectedEof {
+                offset: HEADER_LEN + pos,
+            },
+        )?;
+        pos = next;
+        match op {
+            Op::Pixel(pixel) => {
+                pixel_offset += 4;
+                color_index_array[pixel.hash()] = pixel;
+                prev_pixel = pixel;
+            }
+            Op::Run(run) => {
+                if run > (image_data_len - pixel_offset) / 4 {
+                    return Err(QoiError::TooManyPixels);
+                }
+                pixel_offset += run * 4;
+            }
+        }
+    }
+    reject_trailing_data(skip_end_marker(&bytes[pos..], HEADER_LEN + pos)?)?;
+    Ok(checkpoints)
+}
+
+fn decode_segment(
+    bytes: &[u8],
+    checkpoint: &Checkpoint,
+    segment: &mut [u8],
+) -> Result<(), QoiError> {
+    let mut color_index_array = checkpoint.color_index_array;
+    let mut prev_pixel = checkpoint.prev_pixel;
+    let mut pos = checkpoint.byte_offset;
+    let mut filled = 0;
+    while filled < segment.len() {
+        // The checkpoint scan already walked these ops, so they can't run out of input.
+        let (op, next) = parse_op(bytes, pos, prev_pixel, &color_index_array).ok_or(
+            QoiError::UnexpectedEof {
+                offset: HEADER_LEN + pos,
+            },
+        )?;
+        pos = next;
+        match op {
+            Op::Pixel(pixel) => {
+                segment[filled..filled + 4].copy_from_slice(&pixel.flat());
+                filled += 4;
+                color_index_array[pixel.hash()] = pixel;
+                prev_pixel = pixel;
+            }
+            Op::Run(run) => {
+                let flat_pixel = prev_pixel.flat();
+                segment[filled..filled + run * 4]
+                    .chunks_exact_mut(4)
+                    .for_each(|chunk| chunk.copy_from_slice(&flat_pixel));
+                filled += run * 4;
+            }
+        }
+    }
+    Ok(())
+}
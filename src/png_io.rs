@@ -0,0 +1,349 @@
+use std::io::{Read, Write};
+
+use crate::{Colorspace, ImageData, ImageEncoder, Pixel, QoiError};
+
+/// Adam7 passes as `(x0, y0, dx, dy)`: each pass takes every `dx`th pixel of every `dy`th
+/// row, starting from `(x0, y0)`.
+const ADAM7_PASSES: [(u32, u32, u32, u32); 7] = [
+    (0, 0, 8, 8),
+    (4, 0, 8, 8),
+    (0, 4, 4, 8),
+    (2, 0, 4, 4),
+    (0, 2, 2, 4),
+    (1, 0, 2, 2),
+    (0, 1, 1, 2),
+];
+
+/// How hard the deflate stream inside the PNG is squeezed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PngCompression {
+    Fast,
+    #[default]
+    Default,
+    Best,
+}
+
+impl PngCompression {
+    fn zlib_level(self) -> u8 {
+        match self {
+            Self::Fast => 1,
+            Self::Default => 6,
+            Self::Best => 9,
+        }
+    }
+}
+
+impl From<PngCompression> for png::Compression {
+    fn from(compression: PngCompression) -> Self {
+        match compression {
+            PngCompression::Fast => png::Compression::Fast,
+            PngCompression::Default => png::Compression::Default,
+            PngCompression::Best => png::Compression::Best,
+        }
+    }
+}
+
+/// Bits per channel in the written PNG.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PngBitDepth {
+    #[default]
+    Eight,
+    /// Each 8-bit channel `v` becomes the 16-bit sample `(v << 8) | v`, so 0 and 255 map
+    /// to the ends of the 16-bit range and dropping the low byte gives back `v`.
+    Sixteen,
+}
+
+impl PngBitDepth {
+    fn bytes_per_sample(self) -> usize {
+        match self {
+            Self::Eight => 1,
+            Self::Sixteen => 2,
+        }
+    }
+}
+
+impl From<PngBitDepth> for png::BitDepth {
+    fn from(bit_depth: PngBitDepth) -> Self {
+        match bit_depth {
+            PngBitDepth::Eight => png::BitDepth::Eight,
+            PngBitDepth::Sixteen => png::BitDepth::Sixteen,
+        }
+    }
+}
+
+/// Controls how [`ImageData::write_png_file_with`] writes its output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct PngOptions {
+    /// Declares this colorspace (as an sRGB or gamma chunk) instead of the image's own.
+    pub colorspace: Option<Colorspace>,
+    /// Writes the pixels in Adam7 order, so a viewer can show a coarse version of the whole
+    /// image before all of it has arrived. Interlaced files are usually somewhat larger.
+    pub interlaced: bool,
+    pub compression: PngCompression,
+    pub bit_depth: PngBitDepth,
+}
+
+/// Writes PNG with the given options.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PngEncoder(pub PngOptions);
+
+impl ImageEncoder for PngEncoder {
+    fn encode(&self, image: &ImageData, out_file_buf: &mut dyn Write) -> Result<(), QoiError> {
+        let colorspace = self
+            .0
+            .colorspace
+            .map_or(image.header.colorspace, |colorspace| colorspace as u8);
+        if self.0.interlaced {
+            return image.write_interlaced_png(out_file_buf, colorspace, self.0);
+        }
+        let mut encoder = png::Encoder::new(out_file_buf, image.header.width, image.header.height);
+        encoder.set_compression(self.0.compression.into());
+        encoder.set_depth(self.0.bit_depth.into());
+        match colorspace {
+            0 => encoder.set_source_srgb(png::SrgbRenderingIntent::Perceptual),
+            _ => encoder.set_source_gamma(png::ScaledFloat::new(1.0)),
+        }
+        let samples = match image.header.channels {
+            3 => {
+                encoder.set_color(png::ColorType::Rgb);
+                image.to_rgb_bytes()
+            }
+            4 => {
+                encoder.set_color(png::ColorType::Rgba);
+                image.image_data.clone()
+            }
+            channels => return Err(QoiError::InvalidChannels(channels)),
+        };
+        let samples = match self.0.bit_depth {
+            PngBitDepth::Eight => samples,
+            PngBitDepth::Sixteen => samples.iter().flat_map(|&v| [v, v]).collect(),
+        };
+        encoder.write_header()?.write_image_data(&samples)?;
+        Ok(())
+    }
+}
+
+impl ImageData {
+    pub fn write_png_file(&self, out_file_buf: impl Write) -> Result<(), QoiError> {
+        self.write_png_file_with(out_file_buf, PngOptions::default())
+    }
+
+    pub fn write_png_file_with(
+        &self,
+        out_file_buf: impl Write,
+        options: PngOptions,
+    ) -> Result<(), QoiError> {
+        self.write_with(&PngEncoder(options), out_file_buf)
+    }
+
+    /// Writes an indexed PNG: `palette` goes into the PLTE chunk (plus a tRNS chunk if any
+    /// entry isn't opaque) and `indices` holds each pixel's palette entry in row-major order,
+    /// as returned by [`quantize`](Self::quantize). The image's own pixels are ignored apart
+    /// from its dimensions and colorspace. Indices are packed at the smallest bit depth the
+    /// palette allows, so a handful of colors costs a bit or two per pixel.
+    pub fn write_png_indexed(
+        &self,
+        palette: &[Pixel],
+        indices: &[u8],
+        out_file_buf: impl Write,
+    ) -> Result<(), QoiError> {
+        if palette.is_empty() || palette.len() > 256 {
+            return Err(QoiError::InvalidPaletteSize(palette.len()));
+        }
+        let expected = self.header.width as usize * self.header.height as usize;
+        if indices.len() != expected {
+            return Err(QoiError::BufferSizeMismatch {
+                expected,
+                actual: indices.len(),
+            });
+        }
+        if let Some(&index) = indices.iter().find(|&&i| i as usize >= palette.len()) {
+            return Err(QoiError::PaletteIndexOutOfRange {
+                index,
+                palette_len: palette.len(),
+            });
+        }
+
+        let bits = match palette.len() {
+            1..=2 => 1,
+            3..=4 => 2,
+            5..=16 => 4,
+            _ => 8,
+        };
+        let plte: Vec<u8> = palette.iter().flat_map(|p| [p.r, p.g, p.b]).collect();
+        // Entries past the last translucent one are implicitly opaque, so tRNS stops there.
+        let trns_len = palette
+            .iter()
+            .rposition(|p| p.a != 255)
+            .map_or(0, |i| i + 1);
+        let trns: Vec<u8> = palette[..trns_len].iter().map(|p| p.a).collect();
+
+        let mut encoder = png::Encoder::new(out_file_buf, self.header.width, self.header.height);
+        encoder.set_color(png::ColorType::Indexed);
+        encoder.set_depth(match bits {
+            1 => png::BitDepth::One,
+            2 => png::BitDepth::Two,
+            4 => png::BitDepth::Four,
+            _ => png::BitDepth::Eight,
+        });
+        // Packed indices are a small fraction of the RGBA size, so the slowest level is cheap.
+        encoder.set_compression(png::Compression::Best);
+        encoder.set_palette(plte);
+        if !trns.is_empty() {
+            encoder.set_trns(trns);
+        }
+        match self.header.colorspace {
+            0 => encoder.set_source_srgb(png::SrgbRenderingIntent::Perceptual),
+            _ => encoder.set_source_gamma(png::ScaledFloat::new(1.0)),
+        }
+        let mut packed = Vec::new();
+        if self.header.width > 0 {
+            for row in indices.chunks_exact(self.header.width as usize) {
+                pack_row(row, bits, &mut packed);
+            }
+        }
+        encoder.write_header()?.write_image_data(&packed)?;
+        Ok(())
+    }
+
+    /// The `png` encoder can't interlace, so this filters and compresses the Adam7 passes
+    /// itself: `png` still writes the header chunks, the single IDAT and the IEND.
+    fn write_interlaced_png(
+        &self,
+        out_file_buf: impl Write,
+        colorspace: u8,
+        options: PngOptions,
+    ) -> Result<(), QoiError> {
+        let mut info = png::Info::with_size(self.header.width, self.header.height);
+        info.interlaced = true;
+        info.bit_depth = options.bit_depth.into();
+        info.color_type = match self.header.channels {
+            3 => png::ColorType::Rgb,
+            4 => png::ColorType::Rgba,
+            channels => return Err(QoiError::InvalidChannels(channels)),
+        };
+        match colorspace {
+            0 => info.srgb = Some(png::SrgbRenderingIntent::Perceptual),
+            _ => info.source_gamma = Some(png::ScaledFloat::new(1.0)),
+        }
+        let mut writer = png::Encoder::with_info(out_file_buf, info)?.write_header()?;
+        let filtered = self.adam7_scanlines(options.bit_depth);
+        let level = options.compression.zlib_level();
+        let idat = miniz_oxide::deflate::compress_to_vec_zlib(&filtered, level);
+        writer.write_chunk(png::chunk::IDAT, &idat)?;
+        writer.finish()?;
+        Ok(())
+    }
+
+    /// Every Adam7 pass in order, each row prefixed by its filter type. Rows use the Paeth
+    /// filter against the previous row of the same pass. Empty passes contribute nothing.
+    fn adam7_scanlines(&self, bit_depth: PngBitDepth) -> Vec<u8> {
+        let (width, height) = self.dimensions();
+        let channels = self.header.channels as usize;
+        let sample_len = bit_depth.bytes_per_sample();
+        let bpp = channels * sample_len;
+        let mut out = Vec::new();
+        for (x0, y0, dx, dy) in ADAM7_PASSES {
+            if width <= x0 || height <= y0 {
+                continue;
+            }
+            let pass_width = (width - x0).div_ceil(dx) as usize;
+            let mut prev = vec![0; pass_width * bpp];
+            let mut row = Vec::with_capacity(prev.len());
+            for y in (y0..height).step_by(dy as usize) {
+                row.clear();
+                for x in (x0..width).step_by(dx as usize) {
+                    let offset = (y as usize * width as usize + x as usize) * 4;
+                    for &v in &self.image_data[offset..offset + channels] {
+                        row.extend(core::iter::repeat_n(v, sample_len));
+                    }
+                }
+                out.push(4);
+                for i in 0..row.len() {
+                    let left = if i >= bpp { row[i - bpp] } else { 0 };
+                    let up_left = if i >= bpp { prev[i - bpp] } else { 0 };
+                    out.push(row[i].wrapping_sub(paeth(left, prev[i], up_left)));
+                }
+                core::mem::swap(&mut prev, &mut row);
+            }
+        }
+        out
+    }
+
+    /// Reads any PNG color type at any bit depth: palette images are expanded, grayscale
+    /// becomes RGB and 16-bit samples are cut to their high byte. The image has four
+    /// channels if the source has an alpha channel or a tRNS chunk, and three otherwise.
+    pub fn read_png_file(in_file_buf: impl Read) -> Result<Self, QoiError> {
+        let mut decoder = png::Decoder::new(in_file_buf);
+        // Turns indexed images into RGB, or RGBA if they have a tRNS chunk, widens grayscale
+        // below 8 bits (adding alpha for a tRNS chunk) and keeps only the high byte of
+        // 16-bit samples, leaving just the 8-bit color types below.
+        decoder.set_transformations(png::Transformations::EXPAND | png::Transformations::STRIP_16);
+        let mut reader = decoder.read_info()?;
+        let mut buf = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut buf)?;
+        let (channels, image_data) = match (info.color_type, info.bit_depth) {
+            (png::ColorType::Rgb, png::BitDepth::Eight) => {
+                let image_data = buf[..info.buffer_size()]
+                    .chunks_exact(3)
+                    .flat_map(|chunk| [chunk[0], chunk[1], chunk[2], 255])
+                    .collect();
+                (3, image_data)
+            }
+            (png::ColorType::Rgba, png::BitDepth::Eight) => {
+                buf.truncate(info.buffer_size());
+                (4, buf)
+            }
+            (png::ColorType::Grayscale, png::BitDepth::Eight) => {
+                let image_data = buf[..info.buffer_size()]
+                    .iter()
+                    .flat_map(|&v| [v, v, v, 255])
+                    .collect();
+                (3, image_data)
+            }
+            (png::ColorType::GrayscaleAlpha, png::BitDepth::Eight) => {
+                let image_data = buf[..info.buffer_size()]
+                    .chunks_exact(2)
+                    .flat_map(|chunk| [chunk[0], chunk[0], chunk[0], chunk[1]])
+                    .collect();
+                (4, image_data)
+            }
+            (color_type, bit_depth) => return Err(QoiError::UnsupportedPng(color_type, bit_depth)),
+        };
+        let png_info = reader.info();
+        let is_linear =
+            png_info.srgb.is_none() && png_info.gama_chunk == Some(png::ScaledFloat::new(1.0));
+        let colorspace = if is_linear { 1 } else { 0 };
+        Self::from_rgba(info.width, info.height, channels, colorspace, image_data)
+    }
+}
+
+/// Packs one row of palette indices at `bits` per pixel, most significant bits first, with
+/// the last byte padded out with zeros.
+fn pack_row(row: &[u8], bits: u8, out: &mut Vec<u8>) {
+    let per_byte = (8 / bits) as usize;
+    for chunk in row.chunks(per_byte) {
+        let byte = chunk.iter().enumerate().fold(0, |byte, (i, &index)| {
+            byte | index << (8 - bits * (i as u8 + 1))
+        });
+        out.push(byte);
+    }
+}
+
+/// The PNG Paeth predictor: whichever of left, up and up-left is closest to
+/// `left + up - up_left`.
+fn paeth(left: u8, up: u8, up_left: u8) -> u8 {
+    let estimate = left as i16 + up as i16 - up_left as i16;
+    let (dl, du, dul) = (
+        (estimate - left as i16).abs(),
+        (estimate - up as i16).abs(),
+        (estimate - up_left as i16).abs(),
+    );
+    if dl <= du && dl <= dul {
+        left
+    } else if du <= dul {
+        up
+    } else {
+        up_left
+    }
+}
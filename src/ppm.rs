@@ -0,0 +1,37 @@
+use std::io::Write;
+
+use crate::{ImageData, ImageEncoder, QoiError};
+
+/// Writes a binary Netpbm image: P6 (PPM) for three channels, P7 (PAM) with an alpha
+/// channel for four.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PpmEncoder;
+
+impl ImageEncoder for PpmEncoder {
+    fn encode(&self, image: &ImageData, out_buf: &mut dyn Write) -> Result<(), QoiError> {
+        let (width, height) = (image.header.width, image.header.height);
+        match image.header.channels {
+            3 => {
+                write!(out_buf, "P6\n{} {}\n255\n", width, height)?;
+                out_buf.write_all(&image.to_rgb_bytes())?;
+            }
+            4 => {
+                write!(
+                    out_buf,
+                    "P7\nWIDTH {}\nHEIGHT {}\nDEPTH 4\nMAXVAL 255\nTUPLTYPE RGB_ALPHA\nENDHDR\n",
+                    width, height
+                )?;
+                out_buf.write_all(&image.image_data)?;
+            }
+            channels => return Err(QoiError::InvalidChannels(channels)),
+        }
+        Ok(())
+    }
+}
+
+impl ImageData {
+    /// Shorthand for [`write_with`](Self::write_with) and [`PpmEncoder`].
+    pub fn write_ppm(&self, mut out_buf: impl Write) -> Result<(), QoiError> {
+        self.write_with(&PpmEncoder, &mut out_buf)
+    }
+}
@@ -0,0 +1,37 @@
+use std::io::Read;
+
+use crate::{ImageData, QoiError};
+
+/// A sequence of same-size QOI files stored back to back, each with its own header and end
+/// marker, such as the frames of an animation.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct QoiSequence;
+
+impl QoiSequence {
+    /// Reads the whole stream and decodes frame after frame until it runs out, each one
+    /// starting right after the previous one's end marker. Every frame must have the first
+    /// one's dimensions. Error offsets count from the start of the frame they're in.
+    pub fn decode_all(mut reader: impl Read) -> Result<Vec<ImageData>, QoiError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+        Self::decode_all_slice(&bytes)
+    }
+
+    pub fn decode_all_slice(mut bytes: &[u8]) -> Result<Vec<ImageData>, QoiError> {
+        let mut frames: Vec<ImageData> = Vec::new();
+        while !bytes.is_empty() {
+            let (frame, len) = ImageData::decode_slice_with_len(bytes)?;
+            if let Some(first) = frames.first() {
+                if frame.dimensions() != first.dimensions() {
+                    return Err(QoiError::DimensionMismatch {
+                        expected: first.dimensions(),
+                        actual: frame.dimensions(),
+                    });
+                }
+            }
+            frames.push(frame);
+            bytes = &bytes[len..];
+        }
+        Ok(frames)
+    }
+}
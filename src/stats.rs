@@ -0,0 +1,93 @@
+use alloc::{collections::BTreeMap, vec::Vec};
+
+use crate::{qoi_op_codes::*, ImageData, Pixel};
+
+/// Channel value distributions and the number of distinct colors in an image.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Histogram {
+    pub r: [u64; 256],
+    pub g: [u64; 256],
+    pub b: [u64; 256],
+    pub a: [u64; 256],
+    /// Distinct RGBA tuples.
+    pub unique_colors: u64,
+}
+
+/// How many times a decoded file used each op.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct OpStats {
+    pub rgb: u64,
+    pub rgba: u64,
+    pub index: u64,
+    pub diff: u64,
+    pub luma: u64,
+    pub run: u64,
+    /// Pixels produced by `QOI_OP_RUN`, summed over every run.
+    pub run_pixels: u64,
+}
+
+impl OpStats {
+    pub(crate) fn record(&mut self, op_code: u8, pixels: usize) {
+        match op_code {
+            RGB => self.rgb += 1,
+            RGBA => self.rgba += 1,
+            INDEX::START..=INDEX::END => self.index += 1,
+            DIFF::START..=DIFF::END => self.diff += 1,
+            LUMA::START..=LUMA::END => self.luma += 1,
+            RUN::START..=RUN::END => {
+                self.run += 1;
+                self.run_pixels += pixels as u64;
+            }
+        }
+    }
+}
+
+impl ImageData {
+    pub fn histogram(&self) -> Histogram {
+        let mut histogram = Histogram {
+            r: [0; 256],
+            g: [0; 256],
+            b: [0; 256],
+            a: [0; 256],
+            unique_colors: 0,
+        };
+        let mut colors = Vec::with_capacity(self.image_data.len() / 4);
+        for pixel in self.pixels() {
+            histogram.r[pixel.r as usize] += 1;
+            histogram.g[pixel.g as usize] += 1;
+            histogram.b[pixel.b as usize] += 1;
+            histogram.a[pixel.a as usize] += 1;
+            colors.push(u32::from_be_bytes(pixel.into()));
+        }
+        colors.sort_unstable();
+        colors.dedup();
+        histogram.unique_colors = colors.len() as u64;
+        histogram
+    }
+
+    /// Quantizes each color channel into `buckets` levels and returns the average color of
+    /// the most populous bucket. Fully transparent pixels are ignored for four-channel
+    /// images; if nothing is left, transparent black is returned.
+    pub fn dominant_color(&self, buckets: u8) -> Pixel {
+        let buckets = buckets.max(1) as u32;
+        let quantize = |v: u8| v as u32 * buckets / 256;
+        let mut tallies: BTreeMap<u32, (u64, [u64; 4])> = BTreeMap::new();
+        for pixel in self.pixels() {
+            if self.header.channels == 4 && pixel.a == 0 {
+                continue;
+            }
+            let key =
+                (quantize(pixel.r) * buckets + quantize(pixel.g)) * buckets + quantize(pixel.b);
+            let (count, sums) = tallies.entry(key).or_default();
+            *count += 1;
+            for (sum, value) in sums.iter_mut().zip(<[u8; 4]>::from(pixel)) {
+                *sum += value as u64;
+            }
+        }
+        let Some((count, sums)) = tallies.into_values().max_by_key(|&(count, _)| count) else {
+            return Pixel::rgba(0, 0, 0, 0);
+        };
+        let [r, g, b, a] = sums.map(|sum| ((sum + count / 2) / count) as u8);
+        Pixel::rgba(r, g, b, a)
+    }
+}
@@ -0,0 +1,331 @@
+use std::io::{self, BufRead, BufReader, Read, Seek, SeekFrom, Write};
+
+use crate::{
+    op_len, parse_op, read_exact, EncodeOptions, ImageData, Op, OpEncoder, Pixel, QOIHeader,
+    QoiError, END_MARKER, HEADER_LEN, MAX_RUN,
+};
+
+/// Ops are collected up to this many bytes before being written out.
+const WRITE_BUFFER_LEN: usize = 8 * 1024;
+
+/// Decodes pixels one at a time, pulling input from the reader only as needed.
+pub struct QoiDecoder<R: BufRead> {
+    reader: R,
+    header: QOIHeader,
+    /// How far into the file the reader is, for error positions.
+    offset: usize,
+    remaining_pixels: usize,
+    pending_run: usize,
+    color_index_array: [Pixel; 64],
+    prev_pixel: Pixel,
+    done: bool,
+}
+
+impl<R: BufRead> QoiDecoder<R> {
+    pub fn new(mut reader: R) -> Result<Self, QoiError> {
+        let header = ImageData::read_header(&mut reader)?;
+        let remaining_pixels = header.image_data_len()? / 4;
+        Ok(Self {
+            reader,
+            header,
+            offset: HEADER_LEN,
+            remaining_pixels,
+            pending_run: 0,
+            color_index_array: [Pixel::new(0, 0, 0, 0); 64],
+            prev_pixel: Pixel::new(0, 0, 0, 255),
+            done: false,
+        })
+    }
+
+    pub fn header(&self) -> &QOIHeader {
+        &self.header
+    }
+
+    /// Returns the next pixel, or `None` once every pixel has been produced and the end
+    /// marker has been checked. After an error no further pixels are produced.
+    pub fn next_pixel(&mut self) -> Option<Result<Pixel, QoiError>> {
+        if self.done {
+            return None;
+        }
+        let result = self.decode_next();
+        if !matches!(result, Some(Ok(_))) {
+            self.done = true;
+        }
+        result
+    }
+
+    fn decode_next(&mut self) -> Option<Result<Pixel, QoiError>> {
+        if self.remaining_pixels == 0 {
+            return self.read_end_marker().err().map(Err);
+        }
+        self.remaining_pixels -= 1;
+        if self.pending_run > 0 {
+            self.pending_run -= 1;
+            return Some(Ok(self.prev_pixel));
+        }
+        Some(self.read_op())
+    }
+
+    fn read_op(&mut self) -> Result<Pixel, QoiError> {
+        let mut bytes = [0; 5];
+        read_exact(&mut self.reader, &mut bytes[..1], self.offset)?;
+        let op_len = op_len(bytes[0]);
+        read_exact(&mut self.reader, &mut bytes[1..op_len], self.offset)?;
+        let (op, _) = parse_op(
+            &bytes[..op_len],
+            0,
+            self.prev_pixel,
+            &self.color_index_array,
+        )
+        .ok_or(QoiError::UnexpectedEof {
+            offset: self.offset,
+        })?;
+        self.offset += op_len;
+        match op {
+            Op::Pixel(pixel) => {
+                self.color_index_array[pixel.hash()] = pixel;
+                self.prev_pixel = pixel;
+            }
+            Op::Run(run) => {
+                if run - 1 > self.remaining_pixels {
+                    return Err(QoiError::TooManyPixels);
+                }
+                self.pending_run = run - 1;
+            }
+        }
+        Ok(self.prev_pixel)
+    }
+
+    fn read_end_marker(&mut self) -> Result<(), QoiError> {
+        let mut bytes = [0; END_MARKER.len()];
+        let offset = self.offset;
+        read_exact(&mut self.reader, &mut bytes, offset).map_err(|e| match e {
+            QoiError::UnexpectedEof { .. } => QoiError::MissingEndMarker { offset },
+            e => e,
+        })?;
+        if bytes != END_MARKER {
+            return Err(QoiError::MissingEndMarker { offset });
+        }
+        Ok(())
+    }
+}
+
+/// Reads the decoded image as a stream of RGBA8 bytes, decoding only as the caller asks
+/// for more, so the whole pixel buffer never has to exist at once.
+pub struct RgbaReader<R: BufRead> {
+    decoder: QoiDecoder<R>,
+    /// Bytes of a pixel that didn't fit in the caller's last buffer, from `pending_start`.
+    pending: [u8; 4],
+    pending_start: usize,
+    /// An error hit after some bytes were already read, held back for the next call.
+    error: Option<QoiError>,
+}
+
+impl<R: BufRead> RgbaReader<R> {
+    pub fn new(reader: R) -> Result<Self, QoiError> {
+        Ok(Self {
+            decoder: QoiDecoder::new(reader)?,
+            pending: [0; 4],
+            pending_start: 4,
+            error: None,
+        })
+    }
+
+    pub fn header(&self) -> &QOIHeader {
+        self.decoder.header()
+    }
+}
+
+impl<R: BufRead> Read for RgbaReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if let Some(e) = self.error.take() {
+            return Err(e.into());
+        }
+        let pending = &self.pending[self.pending_start..];
+        let mut written = pending.len().min(buf.len());
+        buf[..written].copy_from_slice(&pending[..written]);
+        self.pending_start += written;
+        while written < buf.len() {
+            let pixel = match self.decoder.next_pixel() {
+                Some(Ok(pixel)) => pixel.flat(),
+                Some(Err(e)) if written == 0 => return Err(e.into()),
+                Some(Err(e)) => {
+                    self.error = Some(e);
+                    break;
+                }
+                None => break,
+            };
+            let len = (buf.len() - written).min(4);
+            buf[written..written + len].copy_from_slice(&pixel[..len]);
+            written += len;
+            self.pending = pixel;
+            self.pending_start = len;
+        }
+        Ok(written)
+    }
+}
+
+/// Encodes pixels one at a time straight into a writer, so an image never has to be held
+/// in memory whole. The header goes out as soon as the writer is created; ops are buffered
+/// a few kilobytes at a time. Call [`finish`](Self::finish) after the last pixel to write
+/// the final run and the end marker: a writer that's just dropped leaves the file
+/// incomplete.
+pub struct QoiWriter<W: Write> {
+    writer: W,
+    encoder: OpEncoder,
+    buffer: Vec<u8>,
+    remaining_pixels: u64,
+    total_pixels: u64,
+}
+
+impl<W: Write> QoiWriter<W> {
+    pub fn new(
+        writer: W,
+        width: u32,
+        height: u32,
+        channels: u8,
+        colorspace: u8,
+    ) -> Result<Self, QoiError> {
+        Self::new_with(
+            writer,
+            QOIHeader::new(width, height, channels, colorspace),
+            EncodeOptions::default(),
+        )
+    }
+
+    /// Like [`new`](Self::new), but encodes with `options`, whose colorspace, if given,
+    /// overrides the header's.
+    pub fn new_with(
+        mut writer: W,
+        mut header: QOIHeader,
+        options: EncodeOptions,
+    ) -> Result<Self, QoiError> {
+        header.validate()?;
+        let total_pixels = (header.image_data_len()? / 4) as u64;
+        if let Some(colorspace) = options.colorspace {
+            header.colorspace = colorspace as u8;
+        }
+        let mut buffer = Vec::with_capacity(WRITE_BUFFER_LEN + 5);
+        header.write(&mut buffer);
+        writer.write_all(&buffer)?;
+        buffer.clear();
+        Ok(Self {
+            writer,
+            encoder: OpEncoder::new(options),
+            buffer,
+            remaining_pixels: total_pixels,
+            total_pixels,
+        })
+    }
+
+    /// Encodes the next pixel in row-major order. Fails with [`QoiError::TooManyPixels`]
+    /// once the header's dimensions have been filled.
+    pub fn write_pixel(&mut self, pixel: Pixel) -> Result<(), QoiError> {
+        if self.remaining_pixels == 0 {
+            return Err(QoiError::TooManyPixels);
+        }
+        self.remaining_pixels -= 1;
+        let buffer = &mut self.buffer;
+        self.encoder.push(pixel, |op| buffer.extend_from_slice(op));
+        if self.buffer.len() >= WRITE_BUFFER_LEN {
+            self.writer.write_all(&self.buffer)?;
+            self.buffer.clear();
+        }
+        Ok(())
+    }
+
+    /// Encodes tightly packed RGBA8 pixels, such as a scanline, in order. Fails with
+    /// [`QoiError::BufferSizeMismatch`], before writing anything, if `rgba` doesn't hold a
+    /// whole number of pixels.
+    pub fn write_rgba(&mut self, rgba: &[u8]) -> Result<(), QoiError> {
+        if !rgba.len().is_multiple_of(4) {
+            return Err(QoiError::BufferSizeMismatch {
+                expected: rgba.len() - rgba.len() % 4,
+                actual: rgba.len(),
+            });
+        }
+        rgba.chunks_exact(4)
+            .try_for_each(|chunk| self.write_pixel(Pixel::from_slice(chunk)))
+    }
+
+    /// Writes the pending run and the end marker, flushes the writer and hands it back.
+    /// Fails with [`QoiError::PixelCountMismatch`] if fewer pixels were written than the
+    /// header declares.
+    pub fn finish(mut self) -> Result<W, QoiError> {
+        if self.remaining_pixels > 0 {
+            return Err(QoiError::PixelCountMismatch {
+                expected: self.total_pixels,
+                got: self.total_pixels - self.remaining_pixels,
+            });
+        }
+        let buffer = &mut self.buffer;
+        self.encoder.finish(|op| buffer.extend_from_slice(op));
+        self.buffer.extend_from_slice(&END_MARKER);
+        self.writer.write_all(&self.buffer)?;
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+impl ImageData {
+    /// Decodes just the top `max_rows` rows (or the whole image, if it has fewer), for a
+    /// quick preview of a large file. Input is read only as far as those rows need, so the
+    /// end marker isn't checked even when every row is requested. A run that continues past
+    /// the last row is simply cut short.
+    pub fn decode_rows(input_buf: impl Read, max_rows: u32) -> Result<Self, QoiError> {
+        let mut decoder = QoiDecoder::new(BufReader::new(input_buf))?;
+        let QOIHeader {
+            width,
+            height,
+            channels,
+            colorspace,
+        } = *decoder.header();
+        let header = QOIHeader::new(width, height.min(max_rows), channels, colorspace);
+        let image_data_len = header.image_data_len()?;
+        // As in `decode_slice`, don't trust the header's dimensions for the reservation: the
+        // input so far can't hold more than a full run per byte, and the buffer grows from
+        // there as pixels arrive.
+        let buffered = decoder.reader.fill_buf()?.len();
+        let mut image_data =
+            Vec::with_capacity(image_data_len.min(buffered.saturating_mul(MAX_RUN as usize * 4)));
+        while image_data.len() < image_data_len {
+            let pixel = decoder.next_pixel().ok_or(QoiError::UnexpectedEof {
+                offset: decoder.offset,
+            })??;
+            image_data.extend_from_slice(&pixel.flat());
+        }
+        Ok(Self { header, image_data })
+    }
+
+    /// Decodes a QOI file stored at byte `offset` of a larger stream, such as a container
+    /// format. The file is decoded as it's read, so reading stops about a buffer's worth past
+    /// the end marker rather than at the end of the stream, and the reader is then left
+    /// just past the end marker. Error offsets count from the start of the QOI file, not
+    /// the stream.
+    pub fn decode_at(mut reader: impl Read + Seek, offset: u64) -> Result<Self, QoiError> {
+        reader.seek(SeekFrom::Start(offset))?;
+        let mut buffered = BufReader::new(reader);
+        let mut decoder = QoiDecoder::new(&mut buffered)?;
+        let header = decoder.header().clone();
+        let image_data_len = header.image_data_len()?;
+        // As in `decode_rows`, the header alone doesn't get to size the reservation.
+        let buffered_len = decoder.reader.fill_buf()?.len();
+        let mut image_data = Vec::with_capacity(
+            image_data_len.min(buffered_len.saturating_mul(MAX_RUN as usize * 4)),
+        );
+        for pixel in &mut decoder {
+            image_data.extend_from_slice(&pixel?.flat());
+        }
+        let len = decoder.offset + END_MARKER.len();
+        buffered.seek(SeekFrom::Start(offset + len as u64))?;
+        Ok(Self { header, image_data })
+    }
+}
+
+impl<R: BufRead> Iterator for QoiDecoder<R> {
+    type Item = Result<Pixel, QoiError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_pixel()
+    }
+}
@@ -0,0 +1,54 @@
+use std::io::Write;
+
+use crate::{swap_rb_inplace, ImageData, ImageEncoder, QoiError};
+
+const UNCOMPRESSED_TRUE_COLOR: u8 = 2;
+const TOP_LEFT_ORIGIN: u8 = 0x20;
+
+/// Writes an uncompressed TGA: 24-bit BGR for three channels, 32-bit BGRA for four.
+/// Rows are stored top to bottom, flagged in the image descriptor byte.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TgaEncoder;
+
+impl ImageEncoder for TgaEncoder {
+    fn encode(&self, image: &ImageData, out_buf: &mut dyn Write) -> Result<(), QoiError> {
+        let (width, height) = (image.header.width, image.header.height);
+        let (bytes_per_pixel, alpha_bits) = match image.header.channels {
+            3 => (3, 0),
+            4 => (4, 8),
+            channels => return Err(QoiError::InvalidChannels(channels)),
+        };
+        let too_large = || QoiError::DimensionsTooLarge { width, height };
+        let width = u16::try_from(width).map_err(|_| too_large())?;
+        let height = u16::try_from(height).map_err(|_| too_large())?;
+
+        let mut bytes = Vec::with_capacity(18 + image.image_data.len() / 4 * bytes_per_pixel);
+        bytes.extend_from_slice(&[0, 0, UNCOMPRESSED_TRUE_COLOR]);
+        bytes.extend_from_slice(&[0; 5]);
+        bytes.extend_from_slice(&0_u16.to_le_bytes());
+        bytes.extend_from_slice(&0_u16.to_le_bytes());
+        bytes.extend_from_slice(&width.to_le_bytes());
+        bytes.extend_from_slice(&height.to_le_bytes());
+        bytes.push(bytes_per_pixel as u8 * 8);
+        bytes.push(TOP_LEFT_ORIGIN | alpha_bits);
+
+        if bytes_per_pixel == 4 {
+            let start = bytes.len();
+            bytes.extend_from_slice(&image.image_data);
+            swap_rb_inplace(&mut bytes[start..]);
+        } else {
+            for chunk in image.image_data.chunks_exact(4) {
+                bytes.extend_from_slice(&[chunk[2], chunk[1], chunk[0]]);
+            }
+        }
+        out_buf.write_all(&bytes)?;
+        Ok(())
+    }
+}
+
+impl ImageData {
+    /// Shorthand for [`write_with`](Self::write_with) and [`TgaEncoder`].
+    pub fn write_tga(&self, mut out_buf: impl Write) -> Result<(), QoiError> {
+        self.write_with(&TgaEncoder, &mut out_buf)
+    }
+}
@@ -0,0 +1,167 @@
+use alloc::{vec, vec::Vec};
+
+use crate::{ImageData, QOIHeader, QoiError};
+
+impl ImageData {
+    /// Copies the `width`x`height` rectangle whose top-left corner is at `(x, y)` into a new
+    /// image.
+    pub fn crop(&self, x: u32, y: u32, width: u32, height: u32) -> Result<Self, QoiError> {
+        let out_of_bounds = || QoiError::RegionOutOfBounds {
+            x,
+            y,
+            width,
+            height,
+        };
+        let right = x.checked_add(width).ok_or_else(out_of_bounds)?;
+        let bottom = y.checked_add(height).ok_or_else(out_of_bounds)?;
+        if right > self.header.width || bottom > self.header.height {
+            return Err(out_of_bounds());
+        }
+        let stride = self.header.width as usize * 4;
+        let row_len = width as usize * 4;
+        let mut image_data = Vec::with_capacity(row_len * height as usize);
+        for row in y as usize..bottom as usize {
+            let start = row * stride + x as usize * 4;
+            image_data.extend_from_slice(&self.image_data[start..start + row_len]);
+        }
+        let header = QOIHeader::new(width, height, self.header.channels, self.header.colorspace);
+        Ok(Self { header, image_data })
+    }
+
+    /// Splits the image into `tile_width`x`tile_height` tiles, left to right and then top to
+    /// bottom. Tiles along the right and bottom edges are clipped to the image, so they may
+    /// be smaller. A zero tile size yields no tiles.
+    pub fn tiles(&self, tile_width: u32, tile_height: u32) -> impl Iterator<Item = Self> + '_ {
+        let (width, height) = self.dimensions();
+        let starts = |len: u32, tile: u32| {
+            let len = if tile == 0 { 0 } else { len };
+            (0..len).step_by(tile.max(1) as usize)
+        };
+        starts(height, tile_height)
+            .flat_map(move |y| starts(width, tile_width).map(move |x| (x, y)))
+            .map(move |(x, y)| {
+                let (w, h) = (tile_width.min(width - x), tile_height.min(height - y));
+                self.crop(x, y, w, h).expect("tile lies within the image")
+            })
+    }
+
+    /// Mirrors the image top to bottom in place.
+    pub fn flip_vertical(&mut self) {
+        let stride = self.header.width as usize * 4;
+        let height = self.header.height as usize;
+        for y in 0..height / 2 {
+            let (top, bottom) = self.image_data.split_at_mut((height - 1 - y) * stride);
+            top[y * stride..(y + 1) * stride].swap_with_slice(&mut bottom[..stride]);
+        }
+    }
+
+    /// Mirrors the image left to right in place.
+    pub fn flip_horizontal(&mut self) {
+        let stride = self.header.width as usize * 4;
+        if stride == 0 {
+            return;
+        }
+        for row in self.image_data.chunks_exact_mut(stride) {
+            let width = row.len() / 4;
+            for x in 0..width / 2 {
+                let (left, right) = row.split_at_mut((width - 1 - x) * 4);
+                left[x * 4..(x + 1) * 4].swap_with_slice(&mut right[..4]);
+            }
+        }
+    }
+
+    /// Returns a copy rotated a quarter turn clockwise.
+    pub fn rotate90(&self) -> Self {
+        let (width, height) = (self.header.height, self.header.width);
+        self.remap(width, height, |x, y| (y, width - 1 - x))
+    }
+
+    /// Returns a copy rotated a half turn.
+    pub fn rotate180(&self) -> Self {
+        let (width, height) = self.dimensions();
+        self.remap(width, height, |x, y| (width - 1 - x, height - 1 - y))
+    }
+
+    /// Returns a copy rotated a quarter turn counter-clockwise.
+    pub fn rotate270(&self) -> Self {
+        let (width, height) = (self.header.height, self.header.width);
+        self.remap(width, height, |x, y| (height - 1 - y, x))
+    }
+
+    /// Scales to `width`x`height` by sampling the source pixel nearest to each target pixel's
+    /// center. Either dimension may be zero; an empty source scales to transparent black.
+    pub fn resize_nearest(&self, width: u32, height: u32) -> Result<Self, QoiError> {
+        let header = QOIHeader::new(width, height, self.header.channels, self.header.colorspace);
+        let image_data_len = header.image_data_len()?;
+        if self.image_data.is_empty() {
+            return Ok(Self {
+                header,
+                image_data: vec![0; image_data_len],
+            });
+        }
+        let scale = |v: u32, src: u32, dst: u32| {
+            ((2 * v as u64 + 1) * src as u64 / (2 * dst as u64)) as u32
+        };
+        let (src_width, src_height) = self.dimensions();
+        Ok(self.remap(width, height, |x, y| {
+            (scale(x, src_width, width), scale(y, src_height, height))
+        }))
+    }
+
+    /// Scales to `width`x`height` by blending the four source pixels around each target
+    /// pixel's center, clamping at the edges. Uses 16.16 fixed point throughout, so results
+    /// are identical on every platform.
+    pub fn resize_bilinear(&self, width: u32, height: u32) -> Result<Self, QoiError> {
+        let header = QOIHeader::new(width, height, self.header.channels, self.header.colorspace);
+        let image_data_len = header.image_data_len()?;
+        if self.image_data.is_empty() {
+            return Ok(Self {
+                header,
+                image_data: vec![0; image_data_len],
+            });
+        }
+        // Maps a target coordinate to the source pixel at or before its center, the next one
+        // over (clamped), and the weight of that next one. The product needs up to 81 bits
+        // before the division brings it back under `src << 16`.
+        let sample = |v: u32, src: u32, dst: u32| {
+            let center = (((2 * v as u128 + 1) << 16) * src as u128 / (2 * dst as u128)) as u64;
+            let pos = center.saturating_sub(1 << 15);
+            let v0 = ((pos >> 16) as u32).min(src - 1);
+            (v0 as usize, (v0 + 1).min(src - 1) as usize, pos & 0xffff)
+        };
+        let (src_width, src_height) = self.dimensions();
+        let stride = src_width as usize * 4;
+        let mut image_data = Vec::with_capacity(image_data_len);
+        for y in 0..height {
+            let (y0, y1, fy) = sample(y, src_height, height);
+            for x in 0..width {
+                let (x0, x1, fx) = sample(x, src_width, width);
+                for c in 0..4 {
+                    let at = |x: usize, y: usize| self.image_data[y * stride + x * 4 + c] as u64;
+                    let top = at(x0, y0) * (0x10000 - fx) + at(x1, y0) * fx;
+                    let bottom = at(x0, y1) * (0x10000 - fx) + at(x1, y1) * fx;
+                    // At most 255 << 32, well within u64.
+                    let value = (top * (0x10000 - fy) + bottom * fy + (1 << 31)) >> 32;
+                    image_data.push(value as u8);
+                }
+            }
+        }
+        Ok(Self { header, image_data })
+    }
+
+    /// Builds a `width`x`height` image whose pixel at `(x, y)` is copied from the source
+    /// coordinate returned by `source`.
+    fn remap(&self, width: u32, height: u32, source: impl Fn(u32, u32) -> (u32, u32)) -> Self {
+        let src_width = self.header.width as usize;
+        let mut image_data = Vec::with_capacity(width as usize * height as usize * 4);
+        for y in 0..height {
+            for x in 0..width {
+                let (src_x, src_y) = source(x, y);
+                let offset = (src_y as usize * src_width + src_x as usize) * 4;
+                image_data.extend_from_slice(&self.image_data[offset..offset + 4]);
+            }
+        }
+        let header = QOIHeader::new(width, height, self.header.channels, self.header.colorspace);
+        Self { header, image_data }
+    }
+}
@@ -0,0 +1,91 @@
+use alloc::vec::Vec;
+
+use crate::{qoi_op_codes::*, ImageData, Pixel, QOIHeader, HEADER_LEN};
+
+impl ImageData {
+    /// Decodes without checking anything: no header validation, no bounds checks on the
+    /// input or output, and no end marker check. For files that have already been checked,
+    /// in code where [`decode_slice`](Self::decode_slice)'s error handling shows up in
+    /// profiles.
+    ///
+    /// # Safety
+    ///
+    /// `bytes` must hold a QOI file that [`decode_slice`](Self::decode_slice) decodes
+    /// successfully. Anything else is undefined behavior: a truncated op stream reads past
+    /// the end of `bytes`, and a run that overshoots the image writes past the end of the
+    /// pixel buffer. Debug builds check the file by decoding it normally first; release
+    /// builds don't.
+    pub unsafe fn decode_trusted(bytes: &[u8]) -> Self {
+        debug_assert!(
+            Self::decode_slice(bytes).is_ok(),
+            "decode_trusted called on a file that doesn't decode"
+        );
+        let be_u32 = |at: usize| {
+            u32::from_be_bytes([bytes[at], bytes[at + 1], bytes[at + 2], bytes[at + 3]])
+        };
+        let header = QOIHeader::new(be_u32(4), be_u32(8), bytes[12], bytes[13]);
+        let image_data_len = header.width as usize * header.height as usize * 4;
+
+        let mut image_data = Vec::<u8>::with_capacity(image_data_len);
+        let out = image_data.as_mut_ptr();
+        let mut color_index_array = [Pixel::new(0, 0, 0, 0); 64];
+        let mut prev_pixel = Pixel::new(0, 0, 0, 255);
+        let mut filled = 0;
+        let mut pos = HEADER_LEN;
+        // SAFETY: the caller guarantees a well-formed file, so every op lies within `bytes`
+        // and the ops add up to exactly `image_data_len` bytes of pixels.
+        unsafe {
+            let byte = |at: usize| *bytes.get_unchecked(at);
+            while filled < image_data_len {
+                let op_code = byte(pos);
+                let pixel = match op_code {
+                    RGB => {
+                        pos += 4;
+                        Pixel::new(byte(pos - 3), byte(pos - 2), byte(pos - 1), prev_pixel.a)
+                    }
+                    RGBA => {
+                        pos += 5;
+                        Pixel::new(byte(pos - 4), byte(pos - 3), byte(pos - 2), byte(pos - 1))
+                    }
+                    INDEX::START..=INDEX::END => {
+                        pos += 1;
+                        *color_index_array.get_unchecked((op_code & 0b111111) as usize)
+                    }
+                    DIFF::START..=DIFF::END => {
+                        pos += 1;
+                        let diff = |shift: u8| ((op_code >> shift) & 0b11).wrapping_sub(2);
+                        prev_pixel.wrapping_add(diff(4), diff(2), diff(0))
+                    }
+                    LUMA::START..=LUMA::END => {
+                        let second = byte(pos + 1);
+                        pos += 2;
+                        let dg = (op_code & 0b111111).wrapping_sub(32);
+                        let dr = dg.wrapping_add((second >> 4).wrapping_sub(8));
+                        let db = dg.wrapping_add((second & 0b1111).wrapping_sub(8));
+                        prev_pixel.wrapping_add(dr, dg, db)
+                    }
+                    RUN::START..=RUN::END => {
+                        pos += 1;
+                        let run = (op_code & 0b111111) as usize + 1;
+                        let end = filled + run * 4;
+                        while filled < end {
+                            out.add(filled)
+                                .cast::<[u8; 4]>()
+                                .write_unaligned(prev_pixel.flat());
+                            filled += 4;
+                        }
+                        continue;
+                    }
+                };
+                out.add(filled)
+                    .cast::<[u8; 4]>()
+                    .write_unaligned(pixel.flat());
+                filled += 4;
+                *color_index_array.get_unchecked_mut(pixel.hash()) = pixel;
+                prev_pixel = pixel;
+            }
+            image_data.set_len(image_data_len);
+        }
+        Self { header, image_data }
+    }
+}
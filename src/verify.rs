@@ -0,0 +1,75 @@
+use crate::{ImageData, Pixel, QOIHeader, QoiError};
+
+/// How two images differ, as reported by [`ImageData::diff`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImageDiff {
+    /// The dimensions, channel count or colorspace differ, so the pixels aren't compared.
+    Header { left: QOIHeader, right: QOIHeader },
+    /// The headers match but some pixels don't. `(x, y)` is the first of them in row-major
+    /// order, holding `left` in one image and `right` in the other; `differing` counts every
+    /// mismatched pixel.
+    Pixels {
+        x: u32,
+        y: u32,
+        left: Pixel,
+        right: Pixel,
+        differing: usize,
+    },
+}
+
+/// Outcome of [`verify_roundtrip`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Roundtrip {
+    Match,
+    /// The re-encoded image decodes differently, starting at this pixel index.
+    Mismatch {
+        first_pixel: usize,
+    },
+}
+
+impl Roundtrip {
+    pub fn is_match(&self) -> bool {
+        *self == Self::Match
+    }
+}
+
+impl ImageData {
+    /// Compares against `other`, returning `None` if the two are equal and otherwise the
+    /// first difference found, along with how many pixels differ in total.
+    pub fn diff(&self, other: &ImageData) -> Option<ImageDiff> {
+        if self.header != other.header {
+            return Some(ImageDiff::Header {
+                left: self.header.clone(),
+                right: other.header.clone(),
+            });
+        }
+        let mut differing = self
+            .enumerate_pixels()
+            .zip(other.pixels())
+            .filter(|((_, _, left), right)| left != right);
+        let ((x, y, left), right) = differing.next()?;
+        Some(ImageDiff::Pixels {
+            x,
+            y,
+            left,
+            right,
+            differing: 1 + differing.count(),
+        })
+    }
+}
+
+/// Decodes `qoi_bytes`, re-encodes the pixels with this crate's encoder and checks that the
+/// result decodes to the same pixels. Only the pixels are compared, since different encoders
+/// may legitimately pick different ops for the same image.
+pub fn verify_roundtrip(qoi_bytes: &[u8]) -> Result<Roundtrip, QoiError> {
+    let original = ImageData::decode_slice(qoi_bytes)?;
+    let reencoded = ImageData::decode_slice(&original.to_qoi_bytes())?;
+    let first_pixel = original
+        .pixels()
+        .zip(reencoded.pixels())
+        .position(|(a, b)| a != b);
+    Ok(match first_pixel {
+        Some(first_pixel) => Roundtrip::Mismatch { first_pixel },
+        None => Roundtrip::Match,
+    })
+}
@@ -0,0 +1,36 @@
+use wasm_bindgen::prelude::*;
+
+use crate::ImageData;
+
+/// A decoded image laid out for `ImageData` on a canvas: tightly packed RGBA8 rows.
+#[wasm_bindgen]
+pub struct DecodedImage {
+    pub width: u32,
+    pub height: u32,
+    pixels: Vec<u8>,
+}
+
+#[wasm_bindgen]
+impl DecodedImage {
+    #[wasm_bindgen(getter)]
+    pub fn pixels(&self) -> Vec<u8> {
+        self.pixels.clone()
+    }
+
+    /// Hands the pixel buffer to JavaScript without copying it on the Rust side.
+    pub fn into_pixels(self) -> Vec<u8> {
+        self.pixels
+    }
+}
+
+#[wasm_bindgen]
+pub fn decode_to_rgba(bytes: &[u8]) -> Result<DecodedImage, JsValue> {
+    let image_data =
+        ImageData::decode_slice(bytes).map_err(|e| JsValue::from_str(&e.to_string()))?;
+    let (width, height) = image_data.dimensions();
+    Ok(DecodedImage {
+        width,
+        height,
+        pixels: image_data.into_rgba_bytes(),
+    })
+}
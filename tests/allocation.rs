@@ -0,0 +1,73 @@
+//! A tiny file declaring huge dimensions mustn't make the decoders, or anything else reading
+//! untrusted files, reserve the declared size up front. Kept to one test so nothing else
+//! allocates in this binary meanwhile.
+
+mod common;
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use qoi_decoder::{row_checksums, ImageData, QoiError};
+
+use common::END_MARKER;
+
+/// Passes everything on to the system allocator, remembering the largest request.
+struct LargestAllocation;
+
+static LARGEST: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for LargestAllocation {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        LARGEST.fetch_max(layout.size(), Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        LARGEST.fetch_max(new_size, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: LargestAllocation = LargestAllocation;
+
+#[test]
+fn huge_header_with_few_ops_allocates_little() {
+    // 20000x20000 is the largest size accepted: 1.6 GB of pixels, from a few bytes of ops.
+    let mut bytes = b"qoif".to_vec();
+    bytes.extend_from_slice(&20_000_u32.to_be_bytes());
+    bytes.extend_from_slice(&20_000_u32.to_be_bytes());
+    bytes.extend_from_slice(&[4, 0, 0xfe, 1, 2, 3, 0xfd, 0xfd]);
+    bytes.extend_from_slice(&END_MARKER);
+    let limit = 1 << 20;
+
+    LARGEST.store(0, Ordering::Relaxed);
+    assert!(ImageData::decode_slice(&bytes).is_err());
+    assert!(LARGEST.load(Ordering::Relaxed) < limit);
+
+    LARGEST.store(0, Ordering::Relaxed);
+    assert!(matches!(
+        ImageData::decode_rows(bytes.as_slice(), u32::MAX),
+        Err(QoiError::UnexpectedEof { .. })
+    ));
+    assert!(LARGEST.load(Ordering::Relaxed) < limit);
+
+    // One pixel per row gives the most rows the limit allows, each wanting a checksum.
+    let mut bytes = b"qoif".to_vec();
+    bytes.extend_from_slice(&1_u32.to_be_bytes());
+    bytes.extend_from_slice(&400_000_000_u32.to_be_bytes());
+    bytes.extend_from_slice(&[4, 0, 0xfe, 1, 2, 3]);
+    bytes.extend_from_slice(&END_MARKER);
+    LARGEST.store(0, Ordering::Relaxed);
+    assert!(matches!(
+        row_checksums(&bytes),
+        Err(QoiError::UnexpectedEof { .. })
+    ));
+    assert!(LARGEST.load(Ordering::Relaxed) < limit);
+}
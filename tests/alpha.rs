@@ -0,0 +1,64 @@
+//! DIFF and LUMA only touch R, G and B; alpha changes solely through RGBA ops.
+
+mod common;
+
+use qoi_decoder::{ImageData, ImageDataBuilder, OpStats, Pixel};
+
+use common::qoi_stream;
+
+#[test]
+fn alpha_survives_long_diff_and_luma_runs() {
+    let (width, height) = (256, 64);
+    let alpha = |y: u32| if y < height / 2 { 200 } else { 77 };
+    let mut pixels = Vec::new();
+    for y in 0..height {
+        for x in 0..width {
+            // Steps of 1 between neighbours give DIFF ops, steps of 8 in green give LUMA.
+            let g = if y % 2 == 0 { x } else { x * 8 };
+            pixels.extend_from_slice(&[(x + y) as u8, g as u8, x as u8, alpha(y)]);
+        }
+    }
+    let image = ImageDataBuilder::new(width, height)
+        .channels(4)
+        .pixels(pixels)
+        .build()
+        .unwrap();
+    let bytes = image.to_qoi_bytes();
+    let (decoded, stats): (ImageData, OpStats) =
+        ImageData::decode_slice_with_stats(&bytes).unwrap();
+    assert!(stats.diff > 5_000 && stats.luma > 5_000, "{:?}", stats);
+    assert!(stats.rgba <= 2, "{:?}", stats);
+    for (i, pixel) in decoded.pixels().enumerate() {
+        assert_eq!(pixel.a, alpha(i as u32 / width), "pixel {}", i);
+    }
+    assert!(decoded.as_rgba_bytes() == image.as_rgba_bytes());
+}
+
+#[test]
+fn wrapping_deltas_do_not_carry_into_alpha() {
+    let ops = [
+        0xff,
+        255,
+        255,
+        255,
+        128,
+        // DIFF +1, +1, +1: every channel wraps to 0
+        0b01_11_11_11,
+        // DIFF -2, -2, -2: every channel wraps back below 0
+        0b01_00_00_00,
+        // LUMA dg = +31, dr - dg = +7, db - dg = +7
+        0b10_111111,
+        0b1111_1111,
+    ];
+    let image = ImageData::decode_slice(&qoi_stream(4, &ops)).unwrap();
+    let pixels: Vec<Pixel> = image.pixels().collect();
+    assert_eq!(
+        pixels,
+        [
+            Pixel::rgba(255, 255, 255, 128),
+            Pixel::rgba(0, 0, 0, 128),
+            Pixel::rgba(254, 254, 254, 128),
+            Pixel::rgba(36, 29, 36, 128),
+        ]
+    );
+}
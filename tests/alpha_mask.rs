@@ -0,0 +1,28 @@
+mod common;
+
+use qoi_decoder::Pixel;
+
+use common::load;
+
+#[test]
+fn mask_replicates_alpha() {
+    let image = load("dice.qoi");
+    let alphas: Vec<u8> = image.pixels().map(|pixel| pixel.a).collect();
+    assert!(alphas.contains(&0) && alphas.contains(&255));
+    assert!(alphas.iter().any(|&a| a != 0 && a != 255));
+
+    let mask = image.alpha_mask();
+    assert_eq!(mask.dimensions(), image.dimensions());
+    assert_eq!(mask.channels(), 3);
+    for (pixel, a) in mask.pixels().zip(alphas) {
+        assert_eq!(pixel, Pixel::rgb(a, a, a));
+    }
+}
+
+#[test]
+fn rgb_source_gives_opaque_mask() {
+    let mask = load("kodim23.qoi").alpha_mask();
+    assert!(mask
+        .pixels()
+        .all(|pixel| pixel == Pixel::rgb(255, 255, 255)));
+}
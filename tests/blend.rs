@@ -0,0 +1,50 @@
+use qoi_decoder::{ImageData, ImageDataBuilder, Pixel, QoiError};
+
+fn solid(width: u32, height: u32, pixel: Pixel) -> ImageData {
+    ImageDataBuilder::new(width, height)
+        .fill(pixel)
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn half_red_over_blue() {
+    let red = solid(4, 3, Pixel::rgba(255, 0, 0, 128));
+    let blue = solid(4, 3, Pixel::rgb(0, 0, 255));
+    let blended = red.blend_over(&blue).unwrap();
+    assert_eq!(blended.dimensions(), (4, 3));
+    assert!(blended
+        .pixels()
+        .all(|pixel| pixel == Pixel::rgba(128, 0, 127, 255)));
+}
+
+#[test]
+fn transparent_layers() {
+    let clear = solid(2, 2, Pixel::rgba(10, 20, 30, 0));
+    let half_green = solid(2, 2, Pixel::rgba(0, 255, 0, 128));
+    let over_clear = half_green.blend_over(&clear).unwrap();
+    assert!(over_clear
+        .pixels()
+        .all(|pixel| pixel == Pixel::rgba(0, 255, 0, 128)));
+    let clear_over = clear.blend_over(&half_green).unwrap();
+    assert!(clear_over
+        .pixels()
+        .all(|pixel| pixel == Pixel::rgba(0, 255, 0, 128)));
+    let nothing = clear.blend_over(&clear).unwrap();
+    assert!(nothing
+        .pixels()
+        .all(|pixel| pixel == Pixel::rgba(0, 0, 0, 0)));
+}
+
+#[test]
+fn mismatched_sizes_are_an_error() {
+    let top = solid(2, 2, Pixel::rgb(0, 0, 0));
+    let bottom = solid(2, 3, Pixel::rgb(0, 0, 0));
+    assert!(matches!(
+        top.blend_over(&bottom),
+        Err(QoiError::DimensionMismatch {
+            expected: (2, 3),
+            actual: (2, 2)
+        })
+    ));
+}
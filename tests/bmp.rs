@@ -0,0 +1,55 @@
+mod common;
+
+use qoi_decoder::ImageData;
+
+use common::load;
+
+/// Reads back the uncompressed bottom-up BMPs written by `write_bmp` into RGBA8.
+fn read_bmp(bytes: &[u8]) -> (u32, u32, Vec<u8>) {
+    let u32_at = |i: usize| u32::from_le_bytes(bytes[i..i + 4].try_into().unwrap());
+    assert_eq!(&bytes[..2], b"BM");
+    assert_eq!(u32_at(2) as usize, bytes.len());
+    let pixel_offset = u32_at(10) as usize;
+    let width = u32_at(18) as usize;
+    let height = u32_at(22) as usize;
+    let bytes_per_pixel = u16::from_le_bytes([bytes[28], bytes[29]]) as usize / 8;
+    let padded_row_len = (width * bytes_per_pixel + 3) & !3;
+    let rows: Vec<&[u8]> = bytes[pixel_offset..].chunks_exact(padded_row_len).collect();
+    assert_eq!(rows.len(), height);
+    let mut rgba = Vec::with_capacity(width * height * 4);
+    for row in rows.iter().rev() {
+        for pixel in row[..width * bytes_per_pixel].chunks_exact(bytes_per_pixel) {
+            let a = if bytes_per_pixel == 4 { pixel[3] } else { 255 };
+            rgba.extend_from_slice(&[pixel[2], pixel[1], pixel[0], a]);
+        }
+    }
+    (width as u32, height as u32, rgba)
+}
+
+#[test]
+fn bmp_round_trip() {
+    for name in ["dice", "kodim23"] {
+        let image_data = load(&format!("{}.qoi", name));
+        let mut bmp = Vec::new();
+        image_data.write_bmp(&mut bmp).unwrap();
+        assert_eq!(bmp[28], image_data.channels() * 8, "{}", name);
+        let (width, height, rgba) = read_bmp(&bmp);
+        assert_eq!((width, height), image_data.dimensions(), "{}", name);
+        assert!(rgba == image_data.as_rgba_bytes(), "{}", name);
+    }
+}
+
+#[test]
+fn rgb_rows_are_padded_to_four_bytes() {
+    let pixels = (0..3 * 2)
+        .flat_map(|i| [i * 10, i * 10 + 1, i * 10 + 2, 255])
+        .collect();
+    let image_data = ImageData::from_rgba(3, 2, 3, 0, pixels).unwrap();
+    let mut bmp = Vec::new();
+    image_data.write_bmp(&mut bmp).unwrap();
+    // 54 header bytes, then two rows of nine bytes plus three of padding.
+    assert_eq!(bmp.len(), 54 + 2 * 12);
+    assert_eq!(&bmp[54 + 9..54 + 12], &[0, 0, 0]);
+    let (_, _, rgba) = read_bmp(&bmp);
+    assert!(rgba == image_data.as_rgba_bytes());
+}
@@ -0,0 +1,298 @@
+mod common;
+
+use std::{
+    env, fs,
+    io::Write,
+    path::PathBuf,
+    process::{self, Command, Output, Stdio},
+};
+
+use qoi_decoder::ImageData;
+
+use common::{has_chunk, load, read};
+
+fn test_image(name: &str) -> String {
+    format!("{}/qoi_test_images/{}", env!("CARGO_MANIFEST_DIR"), name)
+}
+
+fn run(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_qoi-decoder"))
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+/// Runs the binary with `stdin` piped in.
+fn run_with_stdin(args: &[&str], stdin: &[u8]) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_qoi-decoder"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(stdin).unwrap();
+    child.wait_with_output().unwrap()
+}
+
+#[test]
+fn info_prints_each_header() {
+    let dice = test_image("dice.qoi");
+    let kodim = test_image("kodim23.qoi");
+    let output = run(&["--info", &dice, &kodim]);
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        format!(
+            "{}: 800x600, 4 channels, sRGB with linear alpha\n\
+             {}: 768x512, 3 channels, sRGB with linear alpha\n",
+            dice, kodim
+        )
+    );
+}
+
+#[test]
+fn info_fails_on_a_non_qoi_file() {
+    let output = run(&["--info", &test_image("dice.png")]);
+    assert!(!output.status.success());
+    assert!(output.stdout.is_empty());
+}
+
+/// A fresh path under the system temp directory, unique to this test process.
+fn scratch_path(name: &str) -> PathBuf {
+    let path = env::temp_dir().join(format!("qoi-decoder-cli-{}-{}", process::id(), name));
+    let _ = fs::remove_dir_all(&path);
+    let _ = fs::remove_file(&path);
+    path
+}
+
+#[test]
+fn output_dir_is_created_if_missing() {
+    let root = scratch_path("created");
+    let output_dir = root.join("nested");
+    let output = run(&[
+        "--output-dir",
+        output_dir.to_str().unwrap(),
+        &test_image("testcard.qoi"),
+    ]);
+    assert!(output.status.success(), "{:?}", output);
+    let png = fs::read(output_dir.join("testcard.png")).unwrap();
+    assert_eq!(&png[1..4], b"PNG");
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn unusable_output_dir_is_named_once() {
+    let file = scratch_path("not-a-dir");
+    fs::write(&file, b"").unwrap();
+    let output = run(&[
+        "--output-dir",
+        file.to_str().unwrap(),
+        &test_image("dice.qoi"),
+        &test_image("testcard.qoi"),
+    ]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert_eq!(
+        stderr.matches(file.to_str().unwrap()).count(),
+        1,
+        "{}",
+        stderr
+    );
+    assert!(
+        stderr.contains("can't create output directory"),
+        "{}",
+        stderr
+    );
+    fs::remove_file(file).unwrap();
+}
+
+#[test]
+fn output_format_follows_the_extension() {
+    let dir = scratch_path("extensions");
+    fs::create_dir(&dir).unwrap();
+    // kodim23 has three channels, so the Netpbm output is a plain P6 PPM.
+    for (file_name, magic) in [
+        ("out.png", &b"\x89PNG"[..]),
+        ("out.QOI", b"qoif"),
+        ("out.ppm", b"P6"),
+        ("out.pam", b"P6"),
+        ("out.bmp", b"BM"),
+    ] {
+        let path = dir.join(file_name);
+        let output = run(&[&test_image("kodim23.qoi"), path.to_str().unwrap()]);
+        assert!(output.status.success(), "{}: {:?}", file_name, output);
+        assert!(fs::read(&path).unwrap().starts_with(magic), "{}", file_name);
+    }
+    // TGA has no magic number; an uncompressed true-color image is type 2.
+    let path = dir.join("out.tga");
+    assert!(run(&[&test_image("kodim23.qoi"), path.to_str().unwrap()])
+        .status
+        .success());
+    assert_eq!(fs::read(&path).unwrap()[2], 2);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn format_flag_overrides_the_extension() {
+    let dir = scratch_path("format-flag");
+    fs::create_dir(&dir).unwrap();
+    for (file_name, format, magic) in [
+        ("out.png", "qoi", &b"qoif"[..]),
+        ("out.img", "bmp", b"BM"),
+        ("out", "png", b"\x89PNG"),
+    ] {
+        let path = dir.join(file_name);
+        let output = run(&[
+            "--format",
+            format,
+            &test_image("dice.qoi"),
+            path.to_str().unwrap(),
+        ]);
+        assert!(output.status.success(), "{}: {:?}", file_name, output);
+        assert!(fs::read(&path).unwrap().starts_with(magic), "{}", file_name);
+    }
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn unknown_output_extension_is_rejected() {
+    let path = scratch_path("out.xyz");
+    let output = run(&[&test_image("dice.qoi"), path.to_str().unwrap()]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(
+        stderr.contains("can't tell the output format") && stderr.contains("--format"),
+        "{}",
+        stderr
+    );
+    assert!(!path.exists());
+}
+
+#[test]
+fn verify_reports_each_file_and_fails_if_any_does() {
+    let good = test_image("dice.qoi");
+    let corrupt = scratch_path("corrupt.qoi");
+    let bytes = fs::read(&good).unwrap();
+    fs::write(&corrupt, &bytes[..bytes.len() / 2]).unwrap();
+    let output = run(&["--verify", &good, corrupt.to_str().unwrap()]);
+    assert!(!output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines.len(), 2, "{}", stdout);
+    assert_eq!(lines[0], format!("{}: OK", good));
+    assert!(lines[1].starts_with(&format!("{}: ", corrupt.display())));
+    assert_ne!(lines[1], format!("{}: OK", corrupt.display()));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("1 of 2 file(s) failed"), "{}", stderr);
+
+    assert!(run(&["--verify", &good, &test_image("kodim23.qoi")])
+        .status
+        .success());
+    fs::remove_file(corrupt).unwrap();
+}
+
+#[test]
+fn colorspace_flag_overrides_the_inputs() {
+    let dir = scratch_path("colorspace");
+    fs::create_dir(&dir).unwrap();
+    let (qoi, png) = (dir.join("linear.qoi"), dir.join("linear.png"));
+    for path in [&qoi, &png] {
+        let output = run(&[
+            "--colorspace",
+            "linear",
+            &test_image("dice.qoi"),
+            path.to_str().unwrap(),
+        ]);
+        assert!(output.status.success(), "{:?}", output);
+    }
+    assert_eq!(fs::read(test_image("dice.qoi")).unwrap()[13], 0);
+    let qoi_bytes = fs::read(&qoi).unwrap();
+    assert_eq!(qoi_bytes[13], 1);
+    let png_bytes = fs::read(&png).unwrap();
+    assert!(has_chunk(&png_bytes, b"gAMA") && !has_chunk(&png_bytes, b"sRGB"));
+    let image = ImageData::read_png_file(png_bytes.as_slice()).unwrap();
+    assert_eq!(image.colorspace(), 1);
+
+    // And back: the now-linear QOI declared sRGB again.
+    let srgb = dir.join("srgb.png");
+    let output = run(&[
+        "--colorspace",
+        "srgb",
+        qoi.to_str().unwrap(),
+        srgb.to_str().unwrap(),
+    ]);
+    assert!(output.status.success(), "{:?}", output);
+    let png_bytes = fs::read(&srgb).unwrap();
+    assert!(has_chunk(&png_bytes, b"sRGB"));
+    let image = ImageData::read_png_file(png_bytes.as_slice()).unwrap();
+    assert_eq!(image.colorspace(), 0);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn converts_from_stdin_to_stdout() {
+    let output = run_with_stdin(&["-", "-"], &read("dice.qoi"));
+    assert!(output.status.success(), "{:?}", output.stderr);
+    let image = ImageData::read_png_file(output.stdout.as_slice()).unwrap();
+    assert!(image.as_rgba_bytes() == load("dice.qoi").as_rgba_bytes());
+}
+
+#[test]
+fn png_input_is_encoded_to_qoi() {
+    let expected = ImageData::read_png_file(read("dice.png").as_slice()).unwrap();
+    let qoi = scratch_path("from-png.qoi");
+    let output = run(&[&test_image("dice.png"), qoi.to_str().unwrap()]);
+    assert!(output.status.success(), "{:?}", output);
+    assert_eq!(
+        ImageData::decode_slice(&fs::read(&qoi).unwrap()).unwrap(),
+        expected
+    );
+    fs::remove_file(qoi).unwrap();
+
+    // --to-qoi reads the input as PNG whatever it's called, and picks QOI for stdout.
+    let renamed = scratch_path("dice.img");
+    fs::copy(test_image("dice.png"), &renamed).unwrap();
+    let output = run(&["--to-qoi", renamed.to_str().unwrap(), "-"]);
+    assert!(output.status.success(), "{:?}", output);
+    assert_eq!(ImageData::decode_slice(&output.stdout).unwrap(), expected);
+    // Without it the same file is taken for QOI.
+    assert!(!run(&[renamed.to_str().unwrap(), "-"]).status.success());
+    fs::remove_file(renamed).unwrap();
+}
+
+#[test]
+fn inputs_with_the_same_stem_dont_overwrite_each_other() {
+    let root = scratch_path("same-stem");
+    let (a, b, out) = (root.join("a"), root.join("b"), root.join("out"));
+    fs::create_dir_all(&a).unwrap();
+    fs::create_dir_all(&b).unwrap();
+    fs::copy(test_image("dice.qoi"), a.join("x.qoi")).unwrap();
+    fs::copy(test_image("kodim23.qoi"), b.join("x.qoi")).unwrap();
+    let (first, second) = (a.join("x.qoi"), b.join("x.qoi"));
+    let output = run(&[
+        "--jobs",
+        "2",
+        "--output-dir",
+        out.to_str().unwrap(),
+        first.to_str().unwrap(),
+        second.to_str().unwrap(),
+    ]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("1 converted, 1 failed"), "{}", stderr);
+    assert!(
+        stderr.contains(&format!(
+            "{}: {} is already the output of {}",
+            second.display(),
+            out.join("x.png").display(),
+            first.display()
+        )),
+        "{}",
+        stderr
+    );
+    let png = fs::read(out.join("x.png")).unwrap();
+    let image = ImageData::read_png_file(png.as_slice()).unwrap();
+    assert!(image.as_rgba_bytes() == load("dice.qoi").as_rgba_bytes());
+    fs::remove_dir_all(root).unwrap();
+}
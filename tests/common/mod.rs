@@ -0,0 +1,41 @@
+//! Fixtures shared by the integration tests. Each test crate uses only some of them.
+#![allow(dead_code)]
+
+use qoi_decoder::ImageData;
+
+pub const END_MARKER: [u8; 8] = [0, 0, 0, 0, 0, 0, 0, 1];
+
+/// The bytes of a file in `qoi_test_images/`.
+pub fn read(name: &str) -> Vec<u8> {
+    let path = format!("{}/qoi_test_images/{}", env!("CARGO_MANIFEST_DIR"), name);
+    std::fs::read(path).unwrap()
+}
+
+/// A file in `qoi_test_images/`, decoded.
+pub fn load(name: &str) -> ImageData {
+    ImageData::decode_slice(&read(name)).unwrap()
+}
+
+/// A `width`x1 four-channel QOI file made of `ops`.
+pub fn qoi_stream(width: u32, ops: &[u8]) -> Vec<u8> {
+    let mut bytes = b"qoif".to_vec();
+    bytes.extend_from_slice(&width.to_be_bytes());
+    bytes.extend_from_slice(&1_u32.to_be_bytes());
+    bytes.extend_from_slice(&[4, 0]);
+    bytes.extend_from_slice(ops);
+    bytes.extend_from_slice(&END_MARKER);
+    bytes
+}
+
+/// Whether `png` has a chunk of type `name`, found by walking the chunk list.
+pub fn has_chunk(png: &[u8], name: &[u8; 4]) -> bool {
+    let mut pos = 8;
+    while pos + 8 <= png.len() {
+        let len = u32::from_be_bytes(png[pos..pos + 4].try_into().unwrap()) as usize;
+        if &png[pos + 4..pos + 8] == name {
+            return true;
+        }
+        pos += 12 + len;
+    }
+    false
+}
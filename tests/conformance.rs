@@ -0,0 +1,51 @@
+//! Decodes the reference images from the QOI project and checks the pixels against the
+//! PNG versions shipped alongside them. Between them the photos and the synthetic images
+//! exercise all six ops.
+
+use std::fs::File;
+
+use qoi_decoder::ImageData;
+
+const REFERENCE_IMAGES: [&str; 7] = [
+    "dice",
+    "kodim10",
+    "kodim23",
+    "qoi_logo",
+    "testcard",
+    "testcard_rgba",
+    "wikipedia_008",
+];
+
+fn open(name: &str, extension: &str) -> File {
+    let path = format!(
+        "{}/qoi_test_images/{}.{}",
+        env!("CARGO_MANIFEST_DIR"),
+        name,
+        extension
+    );
+    File::open(&path).unwrap_or_else(|e| panic!("{}: {}", path, e))
+}
+
+#[test]
+fn decodes_reference_images() {
+    for name in REFERENCE_IMAGES {
+        let decoded = ImageData::decode(open(name, "qoi")).unwrap();
+        let expected = ImageData::read_png_file(open(name, "png")).unwrap();
+        assert_eq!(decoded.dimensions(), expected.dimensions(), "{}", name);
+        assert!(
+            decoded.as_rgba_bytes() == expected.as_rgba_bytes(),
+            "{}: pixels differ from the reference PNG",
+            name
+        );
+    }
+}
+
+#[test]
+fn reencodes_reference_images_byte_for_byte() {
+    for name in REFERENCE_IMAGES {
+        let mut original = Vec::new();
+        std::io::Read::read_to_end(&mut open(name, "qoi"), &mut original).unwrap();
+        let decoded = ImageData::decode_slice(&original).unwrap();
+        assert!(decoded.to_qoi_bytes() == original, "{}", name);
+    }
+}
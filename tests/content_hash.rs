@@ -0,0 +1,86 @@
+mod common;
+
+use qoi_decoder::{EncodeOptions, ImageData};
+
+use common::load;
+
+#[test]
+fn identical_pixels_hash_identically() {
+    for name in ["dice.qoi", "testcard_rgba.qoi", "kodim10.qoi"] {
+        let image = load(name);
+        let options = EncodeOptions::default().force_rgba(true).use_index(false);
+        let reencoded = ImageData::decode_slice(&image.to_qoi_bytes_with(options)).unwrap();
+        assert_eq!(reencoded.content_hash(), image.content_hash(), "{}", name);
+    }
+}
+
+#[test]
+fn different_images_hash_differently() {
+    let hashes: Vec<u64> = [
+        "dice.qoi",
+        "testcard.qoi",
+        "testcard_rgba.qoi",
+        "kodim10.qoi",
+    ]
+    .into_iter()
+    .map(|name| load(name).content_hash())
+    .collect();
+    for (i, a) in hashes.iter().enumerate() {
+        assert!(hashes[i + 1..].iter().all(|b| a != b));
+    }
+
+    let image = load("dice.qoi");
+    let mut changed = image.clone().into_rgba_bytes();
+    changed[1000] ^= 1;
+    let (width, height) = image.dimensions();
+    let changed = ImageData::from_rgba(width, height, 4, 0, changed).unwrap();
+    assert_ne!(changed.content_hash(), image.content_hash());
+}
+
+#[test]
+fn same_bytes_with_different_header_hash_differently() {
+    let bytes: Vec<u8> = (0..64u8).flat_map(|i| [i, i * 3, 255 - i, 255]).collect();
+    let shaped = |width, height, channels| {
+        ImageData::from_rgba(width, height, channels, 0, bytes.clone())
+            .unwrap()
+            .content_hash()
+    };
+    let hashes = [
+        shaped(8, 8, 4),
+        shaped(4, 16, 4),
+        shaped(16, 4, 4),
+        shaped(64, 1, 4),
+        shaped(1, 64, 4),
+        shaped(8, 8, 3),
+    ];
+    for (i, a) in hashes.iter().enumerate() {
+        assert!(hashes[i + 1..].iter().all(|b| a != b), "{:?}", hashes);
+    }
+    assert_eq!(
+        shaped(8, 8, 4),
+        ImageData::from_rgba(8, 8, 4, 1, bytes.clone())
+            .unwrap()
+            .content_hash()
+    );
+}
+
+#[test]
+fn reordered_blocks_hash_differently() {
+    // Sixteen-byte blocks (four pixels) that differ from each other. The last one starts
+    // with the bytes of a mixing constant the hash once used, which zeroed its state and
+    // made everything before that block irrelevant.
+    let mut blocks: Vec<[u8; 16]> = (0..4u8)
+        .map(|i| core::array::from_fn(|j| i.wrapping_mul(37) ^ j as u8))
+        .collect();
+    blocks[3][..8].copy_from_slice(&0xe703_7ed1_a0b4_28db_u64.to_le_bytes());
+    let hash_of = |order: &[usize]| {
+        let pixels = order.iter().flat_map(|&i| blocks[i]).collect();
+        ImageData::from_rgba(16, 1, 4, 0, pixels)
+            .unwrap()
+            .content_hash()
+    };
+    let original = hash_of(&[0, 1, 2, 3]);
+    for order in [[1, 0, 2, 3], [0, 2, 1, 3], [2, 1, 0, 3], [3, 2, 1, 0]] {
+        assert_ne!(hash_of(&order), original, "{:?}", order);
+    }
+}
@@ -0,0 +1,33 @@
+#![cfg(feature = "tokio")]
+
+mod common;
+
+use std::io::Cursor;
+
+use qoi_decoder::{ImageData, QoiError};
+use tokio::io::BufReader;
+
+use common::read;
+
+#[tokio::test]
+async fn matches_the_sync_decoder() {
+    for name in ["dice.qoi", "testcard_rgba.qoi"] {
+        let bytes = read(name);
+        let decoded = ImageData::decode_async(BufReader::new(Cursor::new(bytes.clone())))
+            .await
+            .unwrap();
+        let expected = ImageData::decode_slice(&bytes).unwrap();
+        assert_eq!(decoded.dimensions(), expected.dimensions());
+        assert!(decoded.as_rgba_bytes() == expected.as_rgba_bytes());
+    }
+}
+
+#[tokio::test]
+async fn reports_decode_errors() {
+    let bytes = read("dice.qoi");
+    let truncated = BufReader::new(Cursor::new(bytes[..bytes.len() - 1].to_vec()));
+    assert!(matches!(
+        ImageData::decode_async(truncated).await,
+        Err(QoiError::MissingEndMarker { .. })
+    ));
+}
@@ -0,0 +1,48 @@
+mod common;
+
+use qoi_decoder::{DecodeContext, DecodeOptions, ImageData};
+
+use common::{qoi_stream, read};
+
+#[test]
+fn matches_decode_slice_across_files() {
+    let truncated = {
+        let bytes = read("kodim10.qoi");
+        bytes[..bytes.len() / 3].to_vec()
+    };
+    // The index op points at a slot only the earlier files would have filled.
+    let fresh_index = qoi_stream(3, &[0xfe, 1, 2, 3, 0x2a, 0xc0]);
+    let files = [
+        (read("kodim23.qoi"), DecodeOptions::default()),
+        (read("qoi_logo.qoi"), DecodeOptions::default()),
+        (read("dice.qoi"), DecodeOptions::default()),
+        (fresh_index, DecodeOptions::default()),
+        (read("testcard.qoi"), DecodeOptions::default()),
+        (read("kodim10.qoi"), DecodeOptions::default()),
+        // Lenient decoding leaves what it couldn't decode as transparent black, not as
+        // whatever the previous image had there.
+        (truncated.clone(), DecodeOptions::default().strict(false)),
+        (read("testcard_rgba.qoi"), DecodeOptions::default()),
+    ];
+    let mut context = DecodeContext::new();
+    for (i, (bytes, options)) in files.iter().enumerate() {
+        let expected = ImageData::decode_slice_with(bytes, *options).unwrap();
+        let decoded = context
+            .decode_file_with(bytes.as_slice(), *options)
+            .unwrap();
+        assert_eq!(decoded.header(), expected.header(), "file {}", i);
+        assert!(
+            decoded.as_rgba_bytes() == expected.as_rgba_bytes(),
+            "file {}",
+            i
+        );
+    }
+
+    // A failure in between doesn't spoil the next file.
+    assert!(context.decode_file(truncated.as_slice()).is_err());
+    let expected = ImageData::decode_slice(&read("wikipedia_008.qoi")).unwrap();
+    let decoded = context
+        .decode_file(read("wikipedia_008.qoi").as_slice())
+        .unwrap();
+    assert_eq!(decoded, &expected);
+}
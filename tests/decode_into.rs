@@ -0,0 +1,27 @@
+mod common;
+
+use qoi_decoder::ImageData;
+
+use common::{load, read};
+
+#[test]
+fn matches_decode_slice() {
+    let mut buffer = Vec::new();
+    for name in ["dice.qoi", "kodim23.qoi", "testcard.qoi"] {
+        let header = ImageData::decode_into(&read(name), &mut buffer).unwrap();
+        let image = load(name);
+        assert_eq!(header, *image.header(), "{}", name);
+        assert!(buffer == image.as_rgba_bytes(), "{}", name);
+    }
+}
+
+#[test]
+fn reuses_the_buffer() {
+    let bytes = read("testcard.qoi");
+    // Stale contents are cleared rather than appended to.
+    let mut buffer = vec![7; 256 * 256 * 4];
+    let ptr = buffer.as_ptr();
+    ImageData::decode_into(&bytes, &mut buffer).unwrap();
+    assert_eq!(buffer.as_ptr(), ptr);
+    assert!(buffer == load("testcard.qoi").as_rgba_bytes());
+}
@@ -0,0 +1,45 @@
+use qoi_decoder::{ImageData, ImageDataBuilder};
+
+/// A 64x40 image whose color changes every `band` rows vertically and every pixel
+/// horizontally when `band` is 1, so larger bands give runs that cross row boundaries.
+fn gradient(band: u32) -> ImageData {
+    let (width, height) = (64, 40);
+    let mut pixels = Vec::new();
+    for y in 0..height {
+        for x in 0..width {
+            let r = if band == 1 { x as u8 * 4 } else { 0 };
+            pixels.extend_from_slice(&[r, (y / band) as u8 * 6, 128, 255]);
+        }
+    }
+    ImageDataBuilder::new(width, height)
+        .pixels(pixels)
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn top_rows_of_a_gradient() {
+    for band in [1, 16] {
+        let image = gradient(band);
+        let bytes = image.to_qoi_bytes();
+        let top = ImageData::decode_rows(bytes.as_slice(), 10).unwrap();
+        assert_eq!(top.dimensions(), (64, 10));
+        assert!(top.as_rgba_bytes() == image.crop(0, 0, 64, 10).unwrap().as_rgba_bytes());
+    }
+}
+
+#[test]
+fn stops_reading_after_the_requested_rows() {
+    let image = gradient(1);
+    let bytes = image.to_qoi_bytes();
+    let truncated = &bytes[..bytes.len() / 2];
+    assert!(ImageData::decode_rows(truncated, 10).is_ok());
+    assert!(ImageData::decode_rows(truncated, 40).is_err());
+}
+
+#[test]
+fn more_rows_than_the_image_has() {
+    let image = gradient(16);
+    let all = ImageData::decode_rows(image.to_qoi_bytes().as_slice(), 1000).unwrap();
+    assert!(all.as_rgba_bytes() == image.as_rgba_bytes());
+}
@@ -0,0 +1,34 @@
+mod common;
+
+use qoi_decoder::ImageData;
+
+use common::read;
+
+#[test]
+fn matches_decoding_from_a_reader() {
+    for name in [
+        "dice.qoi",
+        "kodim10.qoi",
+        "qoi_logo.qoi",
+        "testcard_rgba.qoi",
+    ] {
+        let bytes = read(name);
+        let from_slice = ImageData::decode_slice(&bytes).unwrap();
+        let from_reader = ImageData::decode(bytes.as_slice()).unwrap();
+        assert_eq!(from_slice.header(), from_reader.header(), "{}", name);
+        assert!(
+            from_slice.as_rgba_bytes() == from_reader.as_rgba_bytes(),
+            "{}",
+            name
+        );
+    }
+}
+
+#[test]
+fn errors_match_decoding_from_a_reader() {
+    let bytes = read("dice.qoi");
+    let truncated = &bytes[..bytes.len() / 3];
+    let from_slice = ImageData::decode_slice(truncated).unwrap_err();
+    let from_reader = ImageData::decode(truncated).unwrap_err();
+    assert_eq!(from_slice.to_string(), from_reader.to_string());
+}
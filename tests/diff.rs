@@ -0,0 +1,61 @@
+mod common;
+
+use qoi_decoder::{ImageData, ImageDiff, Pixel};
+
+use common::load;
+
+fn with_pixel(image: &ImageData, x: u32, y: u32, pixel: Pixel) -> ImageData {
+    let mut bytes = image.clone().into_rgba_bytes();
+    let offset = (y * image.width() + x) as usize * 4;
+    bytes[offset..offset + 4].copy_from_slice(&<[u8; 4]>::from(pixel));
+    let (width, height) = image.dimensions();
+    ImageData::from_rgba(width, height, 4, image.colorspace(), bytes).unwrap()
+}
+
+#[test]
+fn identical_images_are_equal() {
+    let image = load("testcard_rgba.qoi");
+    let reencoded = ImageData::decode_slice(&image.to_qoi_bytes()).unwrap();
+    assert_eq!(reencoded, image);
+    assert_eq!(image.diff(&reencoded), None);
+}
+
+#[test]
+fn reports_first_differing_pixel() {
+    let image = load("testcard_rgba.qoi");
+    let black = Pixel::rgba(0, 0, 0, 0);
+    let original = image.get_pixel(7, 3).unwrap();
+    let changed = with_pixel(&with_pixel(&image, 7, 3, black), 100, 50, black);
+    assert_ne!(changed, image);
+    assert_eq!(
+        image.diff(&changed),
+        Some(ImageDiff::Pixels {
+            x: 7,
+            y: 3,
+            left: original,
+            right: black,
+            differing: 2,
+        })
+    );
+}
+
+#[test]
+fn header_mismatch_is_reported_without_comparing_pixels() {
+    let image = load("dice.qoi");
+    let cropped = image.crop(0, 0, image.width() - 1, image.height()).unwrap();
+    assert_ne!(cropped, image);
+    assert!(matches!(
+        image.diff(&cropped),
+        Some(ImageDiff::Header { left, right })
+            if left.width() == image.width() && right.width() == image.width() - 1
+    ));
+
+    let bytes = image.clone().into_rgba_bytes();
+    let (width, height) = image.dimensions();
+    let relabeled = ImageData::from_rgba(width, height, 4, 1, bytes).unwrap();
+    assert_ne!(relabeled, image);
+    assert!(matches!(
+        image.diff(&relabeled),
+        Some(ImageDiff::Header { .. })
+    ));
+}
@@ -0,0 +1,26 @@
+#![cfg(feature = "image")]
+
+mod common;
+
+use image::DynamicImage;
+
+use common::load;
+
+#[test]
+fn keeps_channels_and_pixels() {
+    let rgb = load("kodim23.qoi");
+    let DynamicImage::ImageRgb8(converted) = rgb.to_dynamic_image() else {
+        panic!("expected an RGB8 image");
+    };
+    assert_eq!(converted.dimensions(), rgb.dimensions());
+    for (x, y, pixel) in rgb.enumerate_pixels().step_by(97) {
+        assert_eq!(converted.get_pixel(x, y).0, [pixel.r, pixel.g, pixel.b]);
+    }
+
+    let rgba = load("dice.qoi");
+    let DynamicImage::ImageRgba8(converted) = rgba.to_dynamic_image() else {
+        panic!("expected an RGBA8 image");
+    };
+    assert_eq!(converted.dimensions(), rgba.dimensions());
+    assert!(converted.as_raw().as_slice() == rgba.as_rgba_bytes());
+}
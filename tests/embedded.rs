@@ -0,0 +1,84 @@
+mod common;
+
+use std::io::{Cursor, Read, Seek, SeekFrom};
+
+use qoi_decoder::{ImageData, QoiError};
+
+use common::read;
+
+#[test]
+fn decodes_consecutive_files_from_one_stream() {
+    let (first, second) = (read("dice.qoi"), read("testcard.qoi"));
+    let mut stream = first.clone();
+    stream.extend_from_slice(&second);
+    stream.extend_from_slice(b"more container data");
+
+    let (image, len) = ImageData::decode_slice_with_len(&stream).unwrap();
+    assert_eq!(len, first.len());
+    assert!(image.as_rgba_bytes() == ImageData::decode_slice(&first).unwrap().as_rgba_bytes());
+
+    let (image, len) = ImageData::decode_slice_with_len(&stream[first.len()..]).unwrap();
+    assert_eq!(len, second.len());
+    assert!(image.as_rgba_bytes() == ImageData::decode_slice(&second).unwrap().as_rgba_bytes());
+    assert_eq!(&stream[first.len() + len..], b"more container data");
+}
+
+#[test]
+fn still_requires_the_end_marker() {
+    let bytes = read("dice.qoi");
+    assert!(ImageData::decode_slice_with_len(&bytes[..bytes.len() - 1]).is_err());
+}
+
+/// Counts the bytes read through it.
+struct Counting<R> {
+    inner: R,
+    read: usize,
+}
+
+impl<R: Read> Read for Counting<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.read += n;
+        Ok(n)
+    }
+}
+
+impl<R: Seek> Seek for Counting<R> {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        self.inner.seek(pos)
+    }
+}
+
+#[test]
+fn decodes_at_an_offset_without_reading_the_rest() {
+    let qoi = read("qoi_logo.qoi");
+    let mut container = vec![0xaa; 1234];
+    container.extend_from_slice(&qoi);
+    container.extend_from_slice(&vec![0x55; 1 << 20]);
+    let mut reader = Counting {
+        inner: Cursor::new(container),
+        read: 0,
+    };
+
+    let image = ImageData::decode_at(&mut reader, 1234).unwrap();
+    assert!(image.as_rgba_bytes() == ImageData::decode_slice(&qoi).unwrap().as_rgba_bytes());
+    assert_eq!(reader.inner.position(), (1234 + qoi.len()) as u64);
+    // No further than one buffer's worth past the end marker.
+    assert!(
+        reader.read <= qoi.len() + 8 * 1024,
+        "read {} bytes of a {}-byte file",
+        reader.read,
+        qoi.len()
+    );
+}
+
+#[test]
+fn decode_at_reports_truncation() {
+    let qoi = read("dice.qoi");
+    let mut container = vec![0; 100];
+    container.extend_from_slice(&qoi[..qoi.len() / 2]);
+    assert!(matches!(
+        ImageData::decode_at(Cursor::new(container), 100),
+        Err(QoiError::UnexpectedEof { .. })
+    ));
+}
@@ -0,0 +1,51 @@
+mod common;
+
+use qoi_decoder::{ImageData, ImageDataBuilder, QoiError};
+
+use common::END_MARKER;
+
+fn empty_file(width: u32, height: u32) -> Vec<u8> {
+    let mut bytes = b"qoif".to_vec();
+    bytes.extend_from_slice(&width.to_be_bytes());
+    bytes.extend_from_slice(&height.to_be_bytes());
+    bytes.extend_from_slice(&[4, 0]);
+    bytes.extend_from_slice(&END_MARKER);
+    bytes
+}
+
+#[test]
+fn zero_width_or_height_decodes_to_no_pixels() {
+    for (width, height) in [(0, 5), (5, 0), (0, 0)] {
+        let image = ImageData::decode_slice(&empty_file(width, height)).unwrap();
+        assert_eq!(image.dimensions(), (width, height));
+        assert!(image.as_rgba_bytes().is_empty());
+        assert_eq!(image.pixels().count(), 0);
+    }
+}
+
+#[test]
+fn empty_images_encode_to_header_and_end_marker() {
+    for (width, height) in [(0, 5), (5, 0)] {
+        let image = ImageDataBuilder::new(width, height).build().unwrap();
+        let bytes = image.to_qoi_bytes();
+        assert_eq!(bytes, empty_file(width, height));
+        assert_eq!(
+            ImageData::decode_slice(&bytes).unwrap().dimensions(),
+            (width, height)
+        );
+    }
+}
+
+#[test]
+fn ops_in_an_empty_image_are_rejected() {
+    let mut bytes = empty_file(0, 5);
+    bytes.splice(14..14, [0xfe, 1, 2, 3]);
+    // The ops form a complete one-pixel image, so that's what gets reported.
+    assert!(matches!(
+        ImageData::decode_slice(&bytes),
+        Err(QoiError::PixelCountMismatch {
+            expected: 0,
+            got: 1
+        })
+    ));
+}
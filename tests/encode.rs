@@ -0,0 +1,94 @@
+mod common;
+
+use qoi_decoder::{Colorspace, EncodeOptions, ImageData, ImageDataBuilder, Pixel};
+
+use common::{load, END_MARKER};
+
+fn image_of(pixels: &[Pixel]) -> ImageData {
+    ImageDataBuilder::new(pixels.len() as u32, 1)
+        .pixels(pixels.iter().flat_map(|&p| <[u8; 4]>::from(p)).collect())
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn emits_each_op_kind() {
+    let image = image_of(&[
+        Pixel::rgba(0, 0, 0, 255),
+        Pixel::rgba(1, 1, 1, 255),
+        Pixel::rgba(0, 0, 0, 255),
+        Pixel::rgba(1, 1, 1, 255),
+        Pixel::rgba(10, 20, 30, 40),
+        Pixel::rgba(30, 40, 50, 40),
+        Pixel::rgba(200, 0, 100, 40),
+    ]);
+    let mut expected = b"qoif\0\0\0\x07\0\0\0\x01\x04\0".to_vec();
+    expected.extend_from_slice(&[
+        0xc0, // run of one, matching the initial previous pixel
+        0x7f, // diff +1, +1, +1
+        0x55, // diff -1, -1, -1
+        0x04, // index of (1, 1, 1, 255)
+        0xff, 10, 20, 30, 40, // rgba
+        0xb4, 0x88, // luma +20 on every channel
+        0xfe, 200, 0, 100, // rgb
+    ]);
+    expected.extend_from_slice(&END_MARKER);
+    assert_eq!(image.to_qoi_bytes(), expected);
+}
+
+#[test]
+fn encode_writes_the_same_bytes() {
+    let image = load("testcard_rgba.qoi");
+    let mut written = Vec::new();
+    image.encode(&mut written).unwrap();
+    assert!(written == image.to_qoi_bytes());
+}
+
+#[test]
+fn header_keeps_channels_and_colorspace() {
+    let image = ImageDataBuilder::new(3, 2)
+        .channels(3)
+        .colorspace(1)
+        .fill(Pixel::rgb(9, 8, 7))
+        .build()
+        .unwrap();
+    let bytes = image.to_qoi_bytes();
+    assert_eq!(&bytes[..14], b"qoif\0\0\0\x03\0\0\0\x02\x03\x01");
+    let decoded = ImageData::decode_slice(&bytes).unwrap();
+    assert_eq!((decoded.channels(), decoded.colorspace()), (3, 1));
+    assert!(decoded.pixels().all(|pixel| pixel == Pixel::rgb(9, 8, 7)));
+}
+
+#[test]
+fn encoded_size_matches_the_output() {
+    let options = [
+        EncodeOptions::default(),
+        EncodeOptions::default().use_index(false),
+        EncodeOptions::default().max_run(7),
+        EncodeOptions::default().max_run(1),
+        EncodeOptions::default().force_rgba(true),
+        EncodeOptions::default()
+            .force_rgba(true)
+            .use_index(false)
+            .max_run(30)
+            .colorspace(Some(Colorspace::Linear)),
+    ];
+    for name in [
+        "dice.qoi",
+        "kodim23.qoi",
+        "qoi_logo.qoi",
+        "testcard_rgba.qoi",
+    ] {
+        let image = load(name);
+        assert_eq!(image.encoded_size(), image.to_qoi_bytes().len(), "{}", name);
+        for options in options {
+            assert_eq!(
+                image.encoded_size_with(options),
+                image.to_qoi_bytes_with(options).len(),
+                "{} {:?}",
+                name,
+                options
+            );
+        }
+    }
+}
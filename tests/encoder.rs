@@ -0,0 +1,75 @@
+mod common;
+
+use std::io::{self, Write};
+
+use qoi_decoder::{ImageData, ImageEncoder, QoiEncoder, QoiError};
+
+use common::load;
+
+/// farbfeld: a magic string, the big-endian dimensions, then 16-bit big-endian RGBA.
+struct FarbfeldEncoder;
+
+impl ImageEncoder for FarbfeldEncoder {
+    fn encode(&self, image: &ImageData, out: &mut dyn Write) -> Result<(), QoiError> {
+        out.write_all(b"farbfeld")?;
+        out.write_all(&image.width().to_be_bytes())?;
+        out.write_all(&image.height().to_be_bytes())?;
+        for pixel in image.pixels() {
+            for channel in <[u8; 4]>::from(pixel) {
+                out.write_all(&(channel as u16 * 257).to_be_bytes())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A writer that refuses everything.
+struct Broken;
+
+impl Write for Broken {
+    fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+        Err(io::Error::other("broken"))
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn custom_encoders_plug_into_write_with() {
+    let image = load("qoi_logo.qoi");
+    let mut bytes = Vec::new();
+    image.write_with(&FarbfeldEncoder, &mut bytes).unwrap();
+    let (width, height) = image.dimensions();
+    assert_eq!(bytes.len(), 16 + width as usize * height as usize * 8);
+    assert_eq!(&bytes[..8], b"farbfeld");
+    assert_eq!(bytes[8..12], width.to_be_bytes());
+    assert_eq!(bytes[12..16], height.to_be_bytes());
+    // Every 16-bit channel is its 8-bit value in both bytes.
+    let narrowed: Vec<u8> = bytes[16..].chunks_exact(2).map(|c| c[0]).collect();
+    assert!(narrowed == image.as_rgba_bytes());
+
+    // Chosen at runtime alongside the built-in encoders.
+    let encoders: [Box<dyn ImageEncoder>; 2] =
+        [Box::new(QoiEncoder::default()), Box::new(FarbfeldEncoder)];
+    let outputs: Vec<Vec<u8>> = encoders
+        .iter()
+        .map(|encoder| {
+            let mut out = Vec::new();
+            image.write_with(&**encoder, &mut out).unwrap();
+            out
+        })
+        .collect();
+    assert_eq!(outputs[0], image.to_qoi_bytes());
+    assert_eq!(outputs[1], bytes);
+}
+
+#[test]
+fn write_errors_come_back_from_write_with() {
+    let image = load("qoi_logo.qoi");
+    assert!(matches!(
+        image.write_with(&FarbfeldEncoder, Broken),
+        Err(QoiError::Io(_))
+    ));
+}
@@ -0,0 +1,61 @@
+mod common;
+
+use qoi_decoder::{DecodeOptions, ImageData, QoiError};
+
+use common::read;
+
+fn no_end_marker() -> DecodeOptions {
+    DecodeOptions::default().require_end_marker(false)
+}
+
+#[test]
+fn decodes_without_end_marker_when_not_required() {
+    let bytes = read("dice.qoi");
+    let stripped = &bytes[..bytes.len() - 8];
+    assert!(matches!(
+        ImageData::decode_slice(stripped),
+        Err(QoiError::MissingEndMarker { .. })
+    ));
+    let decoded = ImageData::decode_slice_with(stripped, no_end_marker()).unwrap();
+    let expected = ImageData::decode_slice(&bytes).unwrap();
+    assert!(decoded.as_rgba_bytes() == expected.as_rgba_bytes());
+}
+
+#[test]
+fn ignores_trailing_bytes_when_end_marker_not_required() {
+    let mut bytes = read("dice.qoi");
+    bytes.truncate(bytes.len() - 8);
+    bytes.extend_from_slice(b"container data");
+    assert!(ImageData::decode_slice_with(&bytes, no_end_marker()).is_ok());
+}
+
+#[test]
+fn still_rejects_truncated_pixels() {
+    let bytes = read("dice.qoi");
+    assert!(matches!(
+        ImageData::decode_slice_with(&bytes[..bytes.len() / 2], no_end_marker()),
+        Err(QoiError::UnexpectedEof { .. })
+    ));
+}
+
+#[test]
+fn trailing_bytes_after_the_end_marker_are_rejected() {
+    let mut bytes = read("dice.qoi");
+    bytes.extend_from_slice(b"junk");
+    assert!(matches!(
+        ImageData::decode_slice(&bytes),
+        Err(QoiError::TrailingData(4))
+    ));
+}
+
+#[test]
+fn bytes_in_place_of_the_end_marker_are_a_missing_marker() {
+    let mut bytes = read("dice.qoi");
+    let marker_offset = bytes.len() - 8;
+    bytes.truncate(marker_offset);
+    bytes.extend_from_slice(b"not the marker");
+    assert!(matches!(
+        ImageData::decode_slice(&bytes),
+        Err(QoiError::MissingEndMarker { offset }) if offset == marker_offset
+    ));
+}
@@ -0,0 +1,57 @@
+mod common;
+
+use qoi_decoder::{ImageData, QoiDecoder, QoiError};
+
+use common::read;
+
+fn eof_offset(result: Result<ImageData, QoiError>) -> usize {
+    match result {
+        Err(QoiError::UnexpectedEof { offset }) => offset,
+        other => panic!("expected UnexpectedEof, got {:?}", other.err()),
+    }
+}
+
+#[test]
+fn truncated_header_reports_offset_zero() {
+    let bytes = read("dice.qoi");
+    assert_eq!(eof_offset(ImageData::decode_slice(&bytes[..10])), 0);
+    assert_eq!(eof_offset(ImageData::decode(&bytes[..10])), 0);
+}
+
+#[test]
+fn truncated_ops_report_start_of_cut_op() {
+    let bytes = read("dice.qoi");
+    for len in [14, 15, 100, 1000, bytes.len() / 2, bytes.len() - 9] {
+        let offset = eof_offset(ImageData::decode_slice(&bytes[..len]));
+        // The op that got cut starts at or before the cut and is at most 5 bytes long.
+        assert!(offset <= len && len < offset + 5, "{} at {}", offset, len);
+        assert_eq!(
+            eof_offset(ImageData::decode_slice(&bytes[..offset])),
+            offset
+        );
+
+        let streamed = QoiDecoder::new(&bytes[..len])
+            .unwrap()
+            .find_map(Result::err);
+        assert!(matches!(
+            streamed,
+            Some(QoiError::UnexpectedEof { offset: o }) if o == offset
+        ));
+    }
+}
+
+#[test]
+fn missing_end_marker_reports_where_it_should_be() {
+    let bytes = read("dice.qoi");
+    let marker_at = bytes.len() - 8;
+    for truncated in [&bytes[..marker_at], &bytes[..marker_at + 3]] {
+        assert!(matches!(
+            ImageData::decode_slice(truncated),
+            Err(QoiError::MissingEndMarker { offset }) if offset == marker_at
+        ));
+    }
+    let message = ImageData::decode_slice(&bytes[..marker_at])
+        .unwrap_err()
+        .to_string();
+    assert_eq!(message, format!("missing end marker at byte {}", marker_at));
+}
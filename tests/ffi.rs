@@ -0,0 +1,73 @@
+#![cfg(feature = "ffi")]
+
+mod common;
+
+use std::{ptr, slice};
+
+use qoi_decoder::ffi::{
+    qoi_decode, qoi_free, QOI_ERROR_BAD_MAGIC, QOI_ERROR_INVALID_HEADER,
+    QOI_ERROR_MISSING_END_MARKER, QOI_ERROR_NULL_POINTER, QOI_ERROR_UNEXPECTED_EOF, QOI_OK,
+};
+
+use common::{load, read};
+
+/// Calls `qoi_decode` on `bytes`, copying out the pixels and freeing the buffer on success.
+fn decode(bytes: &[u8]) -> Result<(u32, u32, Vec<u8>), i32> {
+    let (mut width, mut height, mut pixels) = (0, 0, ptr::null_mut());
+    let code = unsafe {
+        qoi_decode(
+            bytes.as_ptr(),
+            bytes.len(),
+            &mut width,
+            &mut height,
+            &mut pixels,
+        )
+    };
+    if code != QOI_OK {
+        assert!(pixels.is_null());
+        return Err(code);
+    }
+    let len = width as usize * height as usize * 4;
+    let copy = unsafe { slice::from_raw_parts(pixels, len) }.to_vec();
+    unsafe { qoi_free(pixels) };
+    Ok((width, height, copy))
+}
+
+#[test]
+fn decodes_like_the_rust_api() {
+    for name in ["dice.qoi", "kodim23.qoi"] {
+        let (width, height, pixels) = decode(&read(name)).unwrap();
+        let expected = load(name);
+        assert_eq!((width, height), expected.dimensions(), "{}", name);
+        assert!(pixels == expected.as_rgba_bytes(), "{}", name);
+    }
+}
+
+#[test]
+fn maps_errors_to_codes() {
+    let bytes = read("dice.qoi");
+    assert_eq!(
+        decode(b"qoix\0\0\0\x01\0\0\0\x01\x04\0"),
+        Err(QOI_ERROR_BAD_MAGIC)
+    );
+    assert_eq!(
+        decode(&bytes[..bytes.len() / 2]),
+        Err(QOI_ERROR_UNEXPECTED_EOF)
+    );
+    assert_eq!(
+        decode(&bytes[..bytes.len() - 8]),
+        Err(QOI_ERROR_MISSING_END_MARKER)
+    );
+    let mut bad_channels = bytes.clone();
+    bad_channels[12] = 7;
+    assert_eq!(decode(&bad_channels), Err(QOI_ERROR_INVALID_HEADER));
+}
+
+#[test]
+fn null_pointers_are_rejected() {
+    let (mut width, mut height) = (0, 0);
+    let code = unsafe { qoi_decode(ptr::null(), 0, &mut width, &mut height, ptr::null_mut()) };
+    assert_eq!(code, QOI_ERROR_NULL_POINTER);
+    // Freeing null is allowed.
+    unsafe { qoi_free(ptr::null_mut()) };
+}
@@ -0,0 +1,66 @@
+use qoi_decoder::{ImageData, ImageDataBuilder};
+
+fn gray_ramp(colorspace: u8) -> ImageData {
+    let pixels = [0, 128, 255]
+        .into_iter()
+        .flat_map(|v| [v, v, v, v])
+        .collect();
+    ImageDataBuilder::new(3, 1)
+        .channels(4)
+        .colorspace(colorspace)
+        .pixels(pixels)
+        .build()
+        .unwrap()
+}
+
+fn assert_close(actual: &[f32], expected: &[f32]) {
+    assert_eq!(actual.len(), expected.len());
+    for (a, e) in actual.iter().zip(expected) {
+        assert!((a - e).abs() < 1e-5, "{:?} != {:?}", actual, expected);
+    }
+}
+
+// 128 / 255, and the same value through the sRGB decoding curve.
+const HALF: f32 = 0.501_960_8;
+const HALF_LINEAR: f32 = 0.215_860_5;
+
+#[test]
+fn normalizes_without_linearizing() {
+    let floats = gray_ramp(0).to_rgba_f32(false);
+    assert_eq!(&floats[..4], [0.0; 4]);
+    assert_close(&floats[4..8], &[HALF; 4]);
+    assert_eq!(&floats[8..], [1.0; 4]);
+}
+
+#[test]
+fn linearizes_srgb_color_but_not_alpha() {
+    let floats = gray_ramp(0).to_rgba_f32(true);
+    assert_eq!(&floats[..4], [0.0; 4]);
+    assert_close(
+        &floats[4..8],
+        &[HALF_LINEAR, HALF_LINEAR, HALF_LINEAR, HALF],
+    );
+    assert_close(&floats[8..], &[1.0; 4]);
+}
+
+#[test]
+fn linear_images_are_never_converted() {
+    let image = gray_ramp(1);
+    assert_eq!(image.to_rgba_f32(true), image.to_rgba_f32(false));
+}
+
+#[test]
+fn dark_values_use_the_linear_segment() {
+    let pixels = vec![10, 10, 10, 255];
+    let image = ImageDataBuilder::new(1, 1).pixels(pixels).build().unwrap();
+    let floats = image.to_rgba_f32(true);
+    assert_close(
+        &floats,
+        &[
+            10.0 / 255.0 / 12.92,
+            10.0 / 255.0 / 12.92,
+            10.0 / 255.0 / 12.92,
+            1.0,
+        ],
+    );
+}
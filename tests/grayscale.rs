@@ -0,0 +1,50 @@
+use qoi_decoder::{ImageData, ImageDataBuilder, Pixel};
+
+fn row(pixels: &[Pixel]) -> ImageData {
+    let image_data = pixels.iter().flat_map(|&p| <[u8; 4]>::from(p)).collect();
+    ImageDataBuilder::new(pixels.len() as u32, 1)
+        .pixels(image_data)
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn known_colors_get_rec709_luma() {
+    let image = row(&[
+        Pixel::rgb(0, 0, 0),
+        Pixel::rgb(255, 255, 255),
+        Pixel::rgb(255, 0, 0),
+        Pixel::rgb(0, 255, 0),
+        Pixel::rgb(0, 0, 255),
+        Pixel::rgb(100, 100, 100),
+        Pixel::rgba(255, 255, 0, 77),
+    ]);
+    let lumas: Vec<(u8, u8, u8, u8)> = image
+        .to_grayscale()
+        .pixels()
+        .map(|p| (p.r, p.g, p.b, p.a))
+        .collect();
+    assert_eq!(
+        lumas,
+        [
+            (0, 0, 0, 255),
+            (255, 255, 255, 255),
+            (54, 54, 54, 255),
+            (182, 182, 182, 255),
+            (18, 18, 18, 255),
+            (100, 100, 100, 255),
+            (237, 237, 237, 77),
+        ]
+    );
+}
+
+#[test]
+fn keeps_the_header() {
+    let image = ImageDataBuilder::new(3, 2)
+        .channels(3)
+        .colorspace(1)
+        .fill(Pixel::rgb(10, 20, 30))
+        .build()
+        .unwrap();
+    assert_eq!(image.to_grayscale().header(), image.header());
+}
@@ -0,0 +1,30 @@
+use qoi_decoder::Pixel;
+
+#[test]
+fn index_hash_matches_spec() {
+    let cases = [
+        ((0, 0, 0, 0), 0),
+        ((0, 0, 0, 255), 53),
+        ((255, 255, 255, 255), 38),
+        ((1, 0, 0, 0), 3),
+        ((0, 1, 0, 0), 5),
+        ((0, 0, 1, 0), 7),
+        ((0, 0, 0, 1), 11),
+        ((255, 0, 0, 255), 50),
+        ((12, 34, 56, 78), 48),
+    ];
+    for ((r, g, b, a), expected) in cases {
+        assert_eq!(
+            Pixel::rgba(r, g, b, a).hash(),
+            expected,
+            "{:?}",
+            (r, g, b, a)
+        );
+    }
+}
+
+#[test]
+fn index_hash_is_const() {
+    const HASH: usize = Pixel::rgb(10, 20, 30).hash();
+    assert_eq!(HASH, (10 * 3 + 20 * 5 + 30 * 7 + 255 * 11) % 64);
+}
@@ -0,0 +1,148 @@
+mod common;
+
+use std::io::{BufRead, BufReader, Cursor};
+
+use qoi_decoder::{ImageData, QoiDecoder, QoiError};
+
+use common::{load, read, END_MARKER};
+
+fn qoi_file(width: u32, height: u32, channels: u8) -> Vec<u8> {
+    let mut bytes = b"qoif".to_vec();
+    bytes.extend_from_slice(&width.to_be_bytes());
+    bytes.extend_from_slice(&height.to_be_bytes());
+    bytes.extend_from_slice(&[channels, 0]);
+    bytes.extend_from_slice(&END_MARKER);
+    bytes
+}
+
+fn decode_error(bytes: &[u8]) -> QoiError {
+    ImageData::decode_slice(bytes).unwrap_err()
+}
+
+#[test]
+fn huge_dimensions_fail_before_allocating() {
+    for (width, height) in [(u32::MAX, u32::MAX), (u32::MAX, 2), (20_000, 20_001)] {
+        let bytes = qoi_file(width, height, 4);
+        assert!(
+            matches!(
+                decode_error(&bytes),
+                QoiError::DimensionsTooLarge { width: w, height: h } if (w, h) == (width, height)
+            ),
+            "{}x{}",
+            width,
+            height
+        );
+        assert!(matches!(
+            QoiDecoder::new(Cursor::new(&bytes)),
+            Err(QoiError::DimensionsTooLarge { .. })
+        ));
+        // Only decoding needs the pixel buffer; the header on its own is fine.
+        let header = ImageData::read_header(bytes.as_slice()).unwrap();
+        assert_eq!((header.width(), header.height()), (width, height));
+    }
+}
+
+#[test]
+fn largest_allowed_dimensions_reach_the_ops() {
+    // 20000x20000 is exactly the limit, so decoding gets as far as finding no ops.
+    assert!(matches!(
+        decode_error(&qoi_file(20_000, 20_000, 4)),
+        QoiError::PixelCountMismatch {
+            expected: 400_000_000,
+            got: 0
+        }
+    ));
+}
+
+#[test]
+fn channels_other_than_3_or_4_are_rejected() {
+    for channels in [0, 1, 2, 5, 7, 255] {
+        let bytes = qoi_file(1, 1, channels);
+        assert!(
+            matches!(decode_error(&bytes), QoiError::InvalidChannels(c) if c == channels),
+            "{}",
+            channels
+        );
+        assert!(matches!(
+            ImageData::read_header(bytes.as_slice()),
+            Err(QoiError::InvalidChannels(_))
+        ));
+    }
+    assert_eq!(
+        decode_error(&qoi_file(1, 1, 7)).to_string(),
+        "invalid channel count: 7"
+    );
+}
+
+#[test]
+fn colorspaces_other_than_0_or_1_are_rejected() {
+    for colorspace in [2, 3, 255] {
+        let mut bytes = qoi_file(1, 1, 4);
+        bytes[13] = colorspace;
+        assert!(
+            matches!(decode_error(&bytes), QoiError::InvalidColorspace(c) if c == colorspace),
+            "{}",
+            colorspace
+        );
+        assert!(matches!(
+            ImageData::read_header(bytes.as_slice()),
+            Err(QoiError::InvalidColorspace(_))
+        ));
+    }
+    let mut linear = qoi_file(0, 0, 4);
+    linear[13] = 1;
+    assert_eq!(ImageData::decode_slice(&linear).unwrap().colorspace(), 1);
+}
+
+#[test]
+fn peek_header_leaves_the_reader_untouched() {
+    let bytes = read("kodim23.qoi");
+    let mut reader = BufReader::new(bytes.as_slice());
+    let header = ImageData::peek_header(&mut reader).unwrap();
+    assert_eq!(
+        (header.width(), header.height(), header.channels()),
+        (768, 512, 3)
+    );
+    let image = ImageData::decode(reader).unwrap();
+    assert_eq!(image.header(), &header);
+    assert!(image.as_rgba_bytes() == load("kodim23.qoi").as_rgba_bytes());
+
+    let png = read("kodim23.png");
+    let mut reader = BufReader::new(png.as_slice());
+    assert!(matches!(
+        ImageData::peek_header(&mut reader),
+        Err(QoiError::BadMagic)
+    ));
+    assert_eq!(reader.fill_buf().unwrap()[..8], png[..8]);
+}
+
+#[test]
+fn peek_header_tells_a_short_buffer_from_the_end_of_input() {
+    // A pipe might deliver only part of the header at first; so does a tiny buffer.
+    let bytes = read("kodim23.qoi");
+    let mut reader = BufReader::with_capacity(8, bytes.as_slice());
+    assert!(matches!(
+        ImageData::peek_header(&mut reader),
+        Err(QoiError::HeaderNotBuffered { buffered: 8 })
+    ));
+    assert_eq!(reader.fill_buf().unwrap(), &bytes[..8]);
+    let header = ImageData::read_header(reader).unwrap();
+    assert_eq!((header.width(), header.height()), (768, 512));
+
+    let mut reader = BufReader::with_capacity(2, bytes.as_slice());
+    assert!(matches!(
+        ImageData::peek_header(&mut reader),
+        Err(QoiError::HeaderNotBuffered { buffered: 2 })
+    ));
+    let mut reader = BufReader::with_capacity(2, &b"\x89PNG"[..]);
+    assert!(matches!(
+        ImageData::peek_header(&mut reader),
+        Err(QoiError::BadMagic)
+    ));
+
+    let mut empty = BufReader::new(&[][..]);
+    assert!(matches!(
+        ImageData::peek_header(&mut empty),
+        Err(QoiError::UnexpectedEof { offset: 0 })
+    ));
+}
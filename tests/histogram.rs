@@ -0,0 +1,65 @@
+use qoi_decoder::{ImageData, ImageDataBuilder, Pixel};
+
+fn image(width: u32, height: u32, pixels: &[Pixel]) -> ImageData {
+    let image_data = pixels.iter().flat_map(|&p| <[u8; 4]>::from(p)).collect();
+    ImageDataBuilder::new(width, height)
+        .pixels(image_data)
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn histogram_counts_channels_and_colors() {
+    let red = Pixel::rgb(255, 0, 0);
+    let histogram = image(
+        3,
+        2,
+        &[
+            red,
+            red,
+            Pixel::rgb(0, 0, 255),
+            Pixel::rgba(255, 0, 0, 0),
+            Pixel::rgba(0, 128, 0, 128),
+            red,
+        ],
+    )
+    .histogram();
+    assert_eq!((histogram.r[255], histogram.r[0]), (4, 2));
+    assert_eq!((histogram.g[0], histogram.g[128]), (5, 1));
+    assert_eq!((histogram.b[0], histogram.b[255]), (5, 1));
+    assert_eq!(
+        (histogram.a[255], histogram.a[128], histogram.a[0]),
+        (4, 1, 1)
+    );
+    for channel in [histogram.r, histogram.g, histogram.b, histogram.a] {
+        assert_eq!(channel.iter().sum::<u64>(), 6);
+    }
+    // Red with zero alpha is a color of its own.
+    assert_eq!(histogram.unique_colors, 4);
+}
+
+#[test]
+fn dominant_color_averages_the_largest_bucket() {
+    let pixels = [
+        Pixel::rgb(200, 10, 10),
+        Pixel::rgb(210, 20, 0),
+        Pixel::rgb(220, 0, 20),
+        Pixel::rgb(0, 0, 250),
+        Pixel::rgb(0, 0, 250),
+        // Transparent pixels don't count, however many there are.
+        Pixel::rgba(0, 255, 0, 0),
+        Pixel::rgba(0, 255, 0, 0),
+        Pixel::rgba(0, 255, 0, 0),
+        Pixel::rgba(0, 255, 0, 0),
+    ];
+    let image = image(3, 3, &pixels);
+    assert_eq!(image.dominant_color(4), Pixel::rgb(210, 10, 10));
+    // With one bucket per channel everything opaque lands together.
+    assert_eq!(image.dominant_color(1), Pixel::rgb(126, 6, 106));
+}
+
+#[test]
+fn dominant_color_of_a_fully_transparent_image() {
+    let image = image(2, 1, &[Pixel::rgba(9, 9, 9, 0); 2]);
+    assert_eq!(image.dominant_color(8), Pixel::rgba(0, 0, 0, 0));
+}
@@ -0,0 +1,89 @@
+mod common;
+
+use qoi_decoder::{ImageData, OpStats};
+
+use common::read;
+
+/// Walks the op stream independently of the decoder, returning the pixel position and
+/// expected color of every `QOI_OP_INDEX`.
+fn index_references(bytes: &[u8]) -> Vec<(usize, [u8; 4])> {
+    let mut index = [[0_u8; 4]; 64];
+    let mut prev = [0, 0, 0, 255];
+    let mut references = Vec::new();
+    let mut position = 0;
+    let mut i = 14;
+    while i < bytes.len() - 8 {
+        let op = bytes[i];
+        let pixel = match op {
+            0xfe => {
+                i += 4;
+                [bytes[i - 3], bytes[i - 2], bytes[i - 1], prev[3]]
+            }
+            0xff => {
+                i += 5;
+                [bytes[i - 4], bytes[i - 3], bytes[i - 2], bytes[i - 1]]
+            }
+            _ => {
+                i += 1;
+                match op >> 6 {
+                    0 => {
+                        references.push((position, index[op as usize]));
+                        index[op as usize]
+                    }
+                    1 => {
+                        let d = |shift: u8| ((op >> shift) & 3).wrapping_sub(2);
+                        [
+                            prev[0].wrapping_add(d(4)),
+                            prev[1].wrapping_add(d(2)),
+                            prev[2].wrapping_add(d(0)),
+                            prev[3],
+                        ]
+                    }
+                    2 => {
+                        let dg = (op & 0x3f).wrapping_sub(32);
+                        let next = bytes[i];
+                        i += 1;
+                        let dr = dg.wrapping_add(next >> 4).wrapping_sub(8);
+                        let db = dg.wrapping_add(next & 0xf).wrapping_sub(8);
+                        [
+                            prev[0].wrapping_add(dr),
+                            prev[1].wrapping_add(dg),
+                            prev[2].wrapping_add(db),
+                            prev[3],
+                        ]
+                    }
+                    _ => {
+                        position += (op & 0x3f) as usize + 1;
+                        continue;
+                    }
+                }
+            }
+        };
+        let [r, g, b, a] = pixel.map(|c| c as usize);
+        index[(r * 3 + g * 5 + b * 7 + a * 11) % 64] = pixel;
+        prev = pixel;
+        position += 1;
+    }
+    references
+}
+
+#[test]
+fn index_ops_resolve_to_expected_colors() {
+    let bytes = read("wikipedia_008.qoi");
+    let (image, stats): (ImageData, OpStats) = ImageData::decode_slice_with_stats(&bytes).unwrap();
+    let references = index_references(&bytes);
+    assert_eq!(references.len() as u64, stats.index);
+    assert!(
+        references.len() > 100_000,
+        "wikipedia_008.qoi should be index heavy"
+    );
+    let pixels = image.as_rgba_bytes();
+    for (position, expected) in references {
+        assert_eq!(
+            pixels[position * 4..position * 4 + 4],
+            expected,
+            "pixel {}",
+            position
+        );
+    }
+}
@@ -0,0 +1,112 @@
+mod common;
+
+use qoi_decoder::{ImageData, ImageDataBuilder, Pixel, QoiError};
+
+use common::load;
+
+fn palette_image(width: u32, height: u32, palette: &[Pixel]) -> ImageData {
+    let pixels = (0..width * height)
+        .flat_map(|i| <[u8; 4]>::from(palette[(i * 7 / 3) as usize % palette.len()]))
+        .collect();
+    ImageDataBuilder::new(width, height)
+        .pixels(pixels)
+        .build()
+        .unwrap()
+}
+
+fn assert_indexed_roundtrip(image: &ImageData, max_colors: usize) -> Vec<u8> {
+    let (palette, indices) = image.quantize(max_colors);
+    let mut png = Vec::new();
+    image
+        .write_png_indexed(&palette, &indices, &mut png)
+        .unwrap();
+    let decoded = ImageData::read_png_file(png.as_slice()).unwrap();
+    assert_eq!(decoded.dimensions(), image.dimensions());
+    assert_eq!(decoded.colorspace(), image.colorspace());
+    for (pixel, index) in decoded.pixels().zip(indices) {
+        assert_eq!(pixel, palette[index as usize]);
+    }
+    png
+}
+
+#[test]
+fn indexed_png_roundtrips_at_every_bit_depth() {
+    let colors: Vec<_> = (0..=255).map(|i| Pixel::rgb(i, 255 - i, i / 2)).collect();
+    for len in [1, 2, 3, 4, 5, 16, 17, 256] {
+        // Odd widths leave a partly filled byte at the end of each packed row.
+        for (width, height) in [(1, 1), (3, 5), (13, 4), (64, 2)] {
+            let image = palette_image(width, height, &colors[..len]);
+            assert_indexed_roundtrip(&image, 256);
+        }
+    }
+}
+
+#[test]
+fn translucent_palette_entries_survive() {
+    let palette = [
+        Pixel::rgba(255, 0, 0, 255),
+        Pixel::rgba(0, 255, 0, 128),
+        Pixel::rgba(0, 0, 255, 0),
+        Pixel::rgba(9, 9, 9, 255),
+    ];
+    let image = palette_image(10, 10, &palette);
+    assert_indexed_roundtrip(&image, 256);
+    let mut png = Vec::new();
+    image
+        .write_png_indexed(&palette, &[0; 100], &mut png)
+        .unwrap();
+    assert_eq!(
+        ImageData::read_png_file(png.as_slice()).unwrap().channels(),
+        4
+    );
+
+    let opaque = [palette[0], palette[3]];
+    let mut png = Vec::new();
+    image
+        .write_png_indexed(&opaque, &[1; 100], &mut png)
+        .unwrap();
+    assert!(!png.windows(4).any(|w| w == b"tRNS"));
+    assert_eq!(
+        ImageData::read_png_file(png.as_slice()).unwrap().channels(),
+        3
+    );
+}
+
+#[test]
+fn indexed_png_is_smaller_for_few_colors() {
+    let image = load("testcard_rgba.qoi");
+    let indexed = assert_indexed_roundtrip(&image, 16);
+    let mut rgba = Vec::new();
+    image.write_png_file(&mut rgba).unwrap();
+    assert!(indexed.len() < rgba.len() / 2);
+}
+
+#[test]
+fn rejects_bad_palettes_and_indices() {
+    let image = palette_image(4, 4, &[Pixel::rgb(1, 2, 3)]);
+    let write = |palette: &[Pixel], indices: &[u8]| {
+        image.write_png_indexed(palette, indices, std::io::sink())
+    };
+    assert!(matches!(
+        write(&[], &[0; 16]),
+        Err(QoiError::InvalidPaletteSize(0))
+    ));
+    assert!(matches!(
+        write(&[Pixel::rgb(0, 0, 0); 257], &[0; 16]),
+        Err(QoiError::InvalidPaletteSize(257))
+    ));
+    assert!(matches!(
+        write(&[Pixel::rgb(0, 0, 0); 2], &[0; 15]),
+        Err(QoiError::BufferSizeMismatch {
+            expected: 16,
+            actual: 15
+        })
+    ));
+    assert!(matches!(
+        write(&[Pixel::rgb(0, 0, 0); 2], &[2; 16]),
+        Err(QoiError::PaletteIndexOutOfRange {
+            index: 2,
+            palette_len: 2
+        })
+    ));
+}
@@ -0,0 +1,39 @@
+mod common;
+
+use qoi_decoder::{ImageData, PngCompression, PngOptions};
+
+use common::load;
+
+fn assert_interlaced_roundtrip(image: &ImageData, compression: PngCompression) {
+    let options = PngOptions {
+        interlaced: true,
+        compression,
+        ..PngOptions::default()
+    };
+    let mut png = Vec::new();
+    image.write_png_file_with(&mut png, options).unwrap();
+    // IHDR's interlace method byte: 8 signature + 8 chunk header + 12 into the data.
+    assert_eq!(png[28], 1);
+    let decoded = ImageData::read_png_file(png.as_slice()).unwrap();
+    assert_eq!(decoded.dimensions(), image.dimensions());
+    assert_eq!(decoded.channels(), image.channels());
+    assert_eq!(decoded.colorspace(), image.colorspace());
+    assert!(decoded.as_rgba_bytes() == image.as_rgba_bytes());
+}
+
+#[test]
+fn interlaced_png_decodes_to_same_pixels() {
+    for name in ["dice.qoi", "testcard.qoi", "kodim10.qoi"] {
+        assert_interlaced_roundtrip(&load(name), PngCompression::Default);
+    }
+}
+
+#[test]
+fn interlaced_png_handles_small_and_odd_sizes() {
+    let image = load("testcard_rgba.qoi");
+    for (width, height) in [(1, 1), (2, 1), (1, 7), (3, 5), (9, 10), (17, 3)] {
+        let cropped = image.crop(0, 0, width, height).unwrap();
+        assert_interlaced_roundtrip(&cropped, PngCompression::Fast);
+        assert_interlaced_roundtrip(&cropped, PngCompression::Best);
+    }
+}
@@ -0,0 +1,26 @@
+mod common;
+
+use qoi_decoder::is_qoi;
+
+use common::read;
+
+#[test]
+fn valid_magic() {
+    assert!(is_qoi(&read("dice.qoi")));
+    assert!(is_qoi(b"qoif\0\0\0\x01\0\0\0\x01\x04\0\0\0\0\0\0\0\0\x01"));
+}
+
+#[test]
+fn wrong_magic() {
+    assert!(!is_qoi(&read("dice.png")));
+    let mut bytes = b"QOIF".to_vec();
+    bytes.resize(22, 0);
+    assert!(!is_qoi(&bytes));
+}
+
+#[test]
+fn too_short() {
+    assert!(!is_qoi(b""));
+    assert!(!is_qoi(b"qoif"));
+    assert!(!is_qoi(b"qoif\0\0\0\x01\0\0\0\x01\x04\0\0\0\0\0\0\0\0"));
+}
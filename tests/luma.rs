@@ -0,0 +1,49 @@
+//! QOI_OP_LUMA at the extremes of its biased ranges, where the wrapping arithmetic is
+//! easiest to get wrong.
+
+mod common;
+
+use qoi_decoder::{ImageData, Pixel};
+
+use common::qoi_stream;
+
+#[test]
+fn luma_extremes() {
+    let ops = [
+        // dg = -32, dr - dg = -8, db - dg = +7, starting from (0, 0, 0)
+        0b10_000000,
+        0b0000_1111,
+        // dg = +31, dr - dg = +7, db - dg = -8
+        0b10_111111,
+        0b1111_0000,
+    ];
+    let bytes = qoi_stream(2, &ops);
+    let image_data = ImageData::decode_slice(&bytes).unwrap();
+    let pixels: Vec<Pixel> = image_data.pixels().collect();
+    assert_eq!(
+        pixels,
+        [
+            // 0 - 40, 0 - 32, 0 - 25, all wrapping
+            Pixel::rgb(216, 224, 231),
+            // 216 + 38, 224 + 31, 231 + 23
+            Pixel::rgb(254, 255, 254),
+        ]
+    );
+    assert_eq!(image_data.to_qoi_bytes(), bytes);
+}
+
+#[test]
+fn luma_wraps_past_255() {
+    let ops = [
+        // (255, 255, 255) via QOI_OP_RGB
+        0b11111110,
+        255,
+        255,
+        255,
+        // dg = +31, dr - dg = +7, db - dg = +7 overflows every channel
+        0b10_111111,
+        0b1111_1111,
+    ];
+    let image_data = ImageData::decode_slice(&qoi_stream(2, &ops)).unwrap();
+    assert_eq!(image_data.get_pixel(1, 0), Some(Pixel::rgb(37, 30, 37)));
+}
@@ -0,0 +1,19 @@
+mod common;
+
+use qoi_decoder::Pixel;
+
+use common::load;
+
+#[test]
+fn invert_twice_is_identity() {
+    let original = load("dice.qoi");
+    let invert = |p: Pixel| Pixel::rgba(255 - p.r, 255 - p.g, 255 - p.b, p.a);
+
+    let mut image = original.clone();
+    image.map_pixels(invert);
+    for (before, after) in original.pixels().zip(image.pixels()) {
+        assert_eq!(after, invert(before));
+    }
+    image.map_pixels(invert);
+    assert!(image.as_rgba_bytes() == original.as_rgba_bytes());
+}
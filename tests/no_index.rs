@@ -0,0 +1,48 @@
+mod common;
+
+use qoi_decoder::{DecodeOptions, DecodeWarning, EncodeOptions, ImageData, Pixel, QoiError};
+
+use common::{load, qoi_stream};
+
+fn assume_no_index() -> DecodeOptions {
+    DecodeOptions::default().assume_no_index(true)
+}
+
+#[test]
+fn decodes_streams_without_index_ops() {
+    let image = load("dice.qoi");
+    let bytes = image.to_qoi_bytes_with(EncodeOptions::default().use_index(false));
+    let decoded = ImageData::decode_slice_with(&bytes, assume_no_index()).unwrap();
+    assert!(decoded.as_rgba_bytes() == image.as_rgba_bytes());
+}
+
+#[test]
+fn index_op_is_an_error() {
+    let bytes = load("dice.qoi").to_qoi_bytes();
+    assert!(matches!(
+        ImageData::decode_slice_with(&bytes, assume_no_index()),
+        Err(QoiError::UnexpectedIndexOp)
+    ));
+}
+
+#[test]
+fn lenient_decoding_warns_about_the_index_op() {
+    // Two pixels, then an index op back to the first.
+    let bytes = qoi_stream(4, &[0xfe, 1, 2, 3, 0xfe, 4, 5, 6, 0x17, 0xc0]);
+    let (image, warnings) =
+        ImageData::decode_slice_with_warnings_with(&bytes, assume_no_index()).unwrap();
+    assert_eq!(warnings, [DecodeWarning::UnexpectedIndexOp { offset: 22 }]);
+    let pixels: Vec<Pixel> = image.pixels().collect();
+    assert_eq!(
+        pixels,
+        [
+            Pixel::rgb(1, 2, 3),
+            Pixel::rgb(4, 5, 6),
+            Pixel::rgba(0, 0, 0, 0),
+            Pixel::rgba(0, 0, 0, 0)
+        ]
+    );
+    // Without the promise the same file is fine.
+    let (_, warnings) = ImageData::decode_slice_with_warnings(&bytes).unwrap();
+    assert_eq!(warnings, []);
+}
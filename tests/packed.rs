@@ -0,0 +1,37 @@
+mod common;
+
+use qoi_decoder::{ImageData, ImageDataBuilder};
+
+use common::read;
+
+#[test]
+fn three_channel_decode_is_packed_rgb() {
+    let rgba: Vec<u8> = (0..6 * 5)
+        .flat_map(|i| [i as u8, (i * 3) as u8, 200, 255])
+        .collect();
+    let image = ImageDataBuilder::new(6, 5)
+        .channels(3)
+        .pixels(rgba.clone())
+        .build()
+        .unwrap();
+    let packed = ImageData::decode_packed(&image.to_qoi_bytes()).unwrap();
+    assert_eq!(packed.dimensions(), (6, 5));
+    assert!(packed.as_rgba_bytes().is_none());
+    let rgb = packed.as_rgb_bytes().unwrap();
+    assert_eq!(rgb.len(), 6 * 5 * 3);
+    let expected: Vec<u8> = rgba
+        .chunks_exact(4)
+        .flat_map(|chunk| &chunk[..3])
+        .copied()
+        .collect();
+    assert_eq!(rgb, expected);
+}
+
+#[test]
+fn four_channel_decode_stays_rgba() {
+    let bytes = read("dice.qoi");
+    let packed = ImageData::decode_packed(&bytes).unwrap();
+    assert!(packed.as_rgb_bytes().is_none());
+    let image = ImageData::decode_slice(&bytes).unwrap();
+    assert!(packed.as_rgba_bytes() == Some(image.as_rgba_bytes()));
+}
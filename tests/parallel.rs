@@ -0,0 +1,155 @@
+#![cfg(feature = "rayon")]
+
+mod common;
+
+use qoi_decoder::{ImageData, ImageDataBuilder, Pixel};
+
+use common::{qoi_stream, read};
+
+const IMAGES: [&str; 7] = [
+    "dice.qoi",
+    "kodim10.qoi",
+    "kodim23.qoi",
+    "qoi_logo.qoi",
+    "testcard.qoi",
+    "testcard_rgba.qoi",
+    "wikipedia_008.qoi",
+];
+
+/// xorshift64, so the op streams are random but the same on every run.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, n: u64) -> u64 {
+        self.next() % n
+    }
+}
+
+/// Decodes `bytes` both ways and checks that the results, or the errors, agree.
+fn assert_same_as_serial(bytes: &[u8], context: &str) {
+    match (
+        ImageData::decode_slice(bytes),
+        ImageData::decode_parallel(bytes),
+    ) {
+        (Ok(serial), Ok(parallel)) => {
+            assert_eq!(serial.header(), parallel.header(), "{}", context);
+            assert!(
+                serial.as_rgba_bytes() == parallel.as_rgba_bytes(),
+                "{}",
+                context
+            );
+        }
+        (serial, parallel) => assert_eq!(
+            format!("{:?}", serial.map(|_| ())),
+            format!("{:?}", parallel.map(|_| ())),
+            "{}",
+            context
+        ),
+    }
+}
+
+/// An image that mixes every op: a small palette gives index hits, repeats give runs and
+/// small nudges give DIFF and LUMA ops.
+fn random_image(rng: &mut Rng, width: u32, height: u32) -> ImageData {
+    let palette: Vec<Pixel> = (0..12)
+        .map(|_| {
+            let [r, g, b, a, ..] = rng.next().to_le_bytes();
+            Pixel::rgba(r, g, b, if a < 128 { 255 } else { a })
+        })
+        .collect();
+    let mut pixel = palette[0];
+    let mut pixels = Vec::with_capacity(width as usize * height as usize * 4);
+    for _ in 0..width * height {
+        pixel = match rng.below(10) {
+            0..=3 => pixel,
+            4..=5 => palette[rng.below(palette.len() as u64) as usize],
+            6..=7 => {
+                let nudge =
+                    |v: u8, rng: &mut Rng| v.wrapping_add(rng.below(5) as u8).wrapping_sub(2);
+                Pixel::rgba(
+                    nudge(pixel.r, rng),
+                    nudge(pixel.g, rng),
+                    nudge(pixel.b, rng),
+                    pixel.a,
+                )
+            }
+            _ => {
+                let [r, g, b, a, ..] = rng.next().to_le_bytes();
+                Pixel::rgba(r, g, b, if a < 200 { pixel.a } else { a })
+            }
+        };
+        pixels.extend_from_slice(&<[u8; 4]>::from(pixel));
+    }
+    ImageDataBuilder::new(width, height)
+        .pixels(pixels)
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn matches_serial_on_test_images() {
+    for name in IMAGES {
+        assert_same_as_serial(&read(name), name);
+    }
+}
+
+#[test]
+fn matches_serial_on_random_streams() {
+    let mut rng = Rng(0x9e37_79b9_7f4a_7c15);
+    for seed in 0..16 {
+        let width = 1 + rng.below(700) as u32;
+        let height = 1 + rng.below(400) as u32;
+        let bytes = random_image(&mut rng, width, height).to_qoi_bytes();
+        assert_same_as_serial(&bytes, &format!("stream {} ({}x{})", seed, width, height));
+    }
+}
+
+#[test]
+fn matches_serial_on_corrupted_streams() {
+    let mut rng = Rng(0x0123_4567_89ab_cdef);
+    let clean = random_image(&mut rng, 300, 300).to_qoi_bytes();
+    for seed in 0..64 {
+        let mut bytes = clean.clone();
+        match seed % 3 {
+            0 => bytes.truncate(rng.below(clean.len() as u64) as usize),
+            _ => {
+                let i = 14 + rng.below(clean.len() as u64 - 14) as usize;
+                bytes[i] = rng.next() as u8;
+            }
+        }
+        assert_same_as_serial(&bytes, &format!("corruption {}", seed));
+    }
+}
+
+#[test]
+fn run_and_index_across_a_checkpoint() {
+    let a = Pixel::rgba(10, 20, 30, 40);
+    // QOI_OP_RGB keeps the previous alpha.
+    let b = Pixel::rgba(200, 100, 50, 40);
+    // QOI_OP_RGBA for `a`, then runs of it up to 65530 pixels.
+    let mut ops = vec![0xff, 10, 20, 30, 40];
+    let mut pixels = 1;
+    while pixels < 65530 {
+        let run = (65530 - pixels).min(62);
+        ops.push(0xc0 | (run as u8 - 1));
+        pixels += run;
+    }
+    // A run over the 65536th pixel, where the first checkpoint falls, then `b` and an index
+    // op back to `a`, which only the carried-over index array still knows about.
+    ops.extend_from_slice(&[0xc0 | 19, 0xfe, 200, 100, 50, a.hash() as u8, 0xc0 | 9]);
+    let bytes = qoi_stream(65562, &ops);
+    let image = ImageData::decode_parallel(&bytes).unwrap();
+    let expected: Vec<Pixel> = std::iter::repeat_n(a, 65550)
+        .chain([b])
+        .chain(std::iter::repeat_n(a, 11))
+        .collect();
+    assert!(image.pixels().eq(expected));
+    assert_same_as_serial(&bytes, "crafted");
+}
@@ -0,0 +1,36 @@
+mod common;
+
+use qoi_decoder::ImageData;
+
+use common::read;
+
+#[test]
+fn truncated_file_decodes_partially() {
+    let bytes = read("kodim23.qoi");
+    let full = ImageData::decode_slice(&bytes).unwrap();
+    let total_pixels = full.as_rgba_bytes().len() as u64 / 4;
+
+    let partial = ImageData::decode_partial(&bytes[..bytes.len() / 2]).unwrap();
+    assert!(!partial.is_complete());
+    let decoded = partial.decoded_pixels();
+    assert!(
+        decoded > total_pixels / 3 && decoded < total_pixels * 2 / 3,
+        "{} of {}",
+        decoded,
+        total_pixels
+    );
+    let decoded_len = decoded as usize * 4;
+    let image = partial.into_image();
+    assert_eq!(image.dimensions(), full.dimensions());
+    assert_eq!(
+        image.as_rgba_bytes()[..decoded_len],
+        full.as_rgba_bytes()[..decoded_len]
+    );
+    assert!(image.as_rgba_bytes()[decoded_len..].iter().all(|&b| b == 0));
+}
+
+#[test]
+fn complete_file_decodes_fully() {
+    let partial = ImageData::decode_partial(&read("dice.qoi")).unwrap();
+    assert!(partial.is_complete());
+}
@@ -0,0 +1,82 @@
+mod common;
+
+use qoi_decoder::{DecodeOptions, ImageData, ImageDataBuilder, Pixel, QoiError};
+
+use common::read;
+
+fn with_dimensions(mut bytes: Vec<u8>, width: u32, height: u32) -> Vec<u8> {
+    bytes[4..8].copy_from_slice(&width.to_be_bytes());
+    bytes[8..12].copy_from_slice(&height.to_be_bytes());
+    bytes
+}
+
+fn pixel_count_mismatch(bytes: &[u8]) -> (u64, u64) {
+    match ImageData::decode_slice(bytes) {
+        Err(QoiError::PixelCountMismatch { expected, got }) => (expected, got),
+        other => panic!("expected PixelCountMismatch, got {:?}", other),
+    }
+}
+
+#[test]
+fn header_claiming_too_few_or_too_many_pixels() {
+    let bytes = read("dice.qoi");
+    let (width, height) = ImageData::decode_slice(&bytes).unwrap().dimensions();
+    let actual = width as u64 * height as u64;
+    for (w, h) in [
+        (width, height - 1),
+        (width, height + 1),
+        (width - 1, height),
+        (1, 1),
+    ] {
+        let crafted = with_dimensions(bytes.clone(), w, h);
+        assert_eq!(
+            pixel_count_mismatch(&crafted),
+            (w as u64 * h as u64, actual),
+            "{}x{}",
+            w,
+            h
+        );
+    }
+}
+
+#[test]
+fn run_overshooting_a_short_header() {
+    let pixels = std::iter::repeat_n(<[u8; 4]>::from(Pixel::rgb(10, 20, 30)), 16 * 16)
+        .flatten()
+        .collect();
+    let image = ImageDataBuilder::new(16, 16)
+        .pixels(pixels)
+        .build()
+        .unwrap();
+    let crafted = with_dimensions(image.to_qoi_bytes(), 10, 10);
+    assert_eq!(pixel_count_mismatch(&crafted), (100, 256));
+}
+
+#[test]
+fn matching_pixel_count_still_decodes() {
+    let bytes = read("dice.qoi");
+    let (width, height) = ImageData::decode_slice(&bytes).unwrap().dimensions();
+    // Same number of pixels, just laid out differently: nothing to detect.
+    let swapped = with_dimensions(bytes.clone(), height, width);
+    assert_eq!(
+        ImageData::decode_slice(&swapped).unwrap().dimensions(),
+        (height, width)
+    );
+}
+
+#[test]
+fn truncated_files_keep_their_own_errors() {
+    let bytes = read("dice.qoi");
+    assert!(matches!(
+        ImageData::decode_slice(&bytes[..bytes.len() - 8]),
+        Err(QoiError::MissingEndMarker { .. })
+    ));
+    assert!(matches!(
+        ImageData::decode_slice(&bytes[..bytes.len() / 2]),
+        Err(QoiError::UnexpectedEof { .. })
+    ));
+    // Lenient decoding reports the mismatch as a warning rather than an error.
+    let crafted = with_dimensions(bytes, 1, 1);
+    let lenient = DecodeOptions::default().strict(false);
+    assert!(ImageData::decode_slice_with(&crafted, lenient).is_ok());
+}
@@ -0,0 +1,55 @@
+mod common;
+
+use qoi_decoder::{ImageData, PngBitDepth, PngOptions};
+
+use common::load;
+
+fn write_16_bit(image: &ImageData, interlaced: bool) -> Vec<u8> {
+    let options = PngOptions {
+        bit_depth: PngBitDepth::Sixteen,
+        interlaced,
+        ..PngOptions::default()
+    };
+    let mut png = Vec::new();
+    image.write_png_file_with(&mut png, options).unwrap();
+    png
+}
+
+#[test]
+fn sixteen_bit_png_truncates_back_to_original() {
+    for name in ["dice.qoi", "testcard.qoi", "qoi_logo.qoi"] {
+        let image = load(name);
+        for interlaced in [false, true] {
+            let png = write_16_bit(&image, interlaced);
+            // IHDR's bit depth byte: 8 signature + 8 chunk header + 8 into the data.
+            assert_eq!(png[24], 16);
+            let decoded = ImageData::read_png_file(png.as_slice()).unwrap();
+            assert_eq!(decoded.dimensions(), image.dimensions());
+            assert_eq!(decoded.channels(), image.channels());
+            assert!(decoded.as_rgba_bytes() == image.as_rgba_bytes());
+        }
+    }
+}
+
+#[test]
+fn samples_replicate_into_both_bytes_big_endian() {
+    let image = load("testcard_rgba.qoi").crop(0, 0, 9, 7).unwrap();
+    for interlaced in [false, true] {
+        let png = write_16_bit(&image, interlaced);
+        let mut reader = png::Decoder::new(png.as_slice()).read_info().unwrap();
+        let mut buf = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut buf).unwrap();
+        assert_eq!(info.bit_depth, png::BitDepth::Sixteen);
+        assert_eq!(info.color_type, png::ColorType::Rgba);
+        let samples: Vec<u16> = buf[..info.buffer_size()]
+            .chunks_exact(2)
+            .map(|pair| u16::from_be_bytes([pair[0], pair[1]]))
+            .collect();
+        let expected: Vec<u16> = image
+            .as_rgba_bytes()
+            .iter()
+            .map(|&v| (v as u16) << 8 | v as u16)
+            .collect();
+        assert_eq!(samples, expected);
+    }
+}
@@ -0,0 +1,149 @@
+use qoi_decoder::{ImageData, Pixel};
+
+/// Writes a PNG of `color_type` and `bit_depth` from raw, already packed scanlines.
+fn png(
+    (width, height): (u32, u32),
+    color_type: png::ColorType,
+    bit_depth: png::BitDepth,
+    trns: Option<&[u8]>,
+    data: &[u8],
+) -> Vec<u8> {
+    let mut out = Vec::new();
+    let mut encoder = png::Encoder::new(&mut out, width, height);
+    encoder.set_color(color_type);
+    encoder.set_depth(bit_depth);
+    if let Some(trns) = trns {
+        encoder.set_trns(trns.to_vec());
+    }
+    encoder
+        .write_header()
+        .unwrap()
+        .write_image_data(data)
+        .unwrap();
+    out
+}
+
+/// Reads the PNG, round-trips it through QOI and checks the pixels and channel count.
+fn assert_reads_as(png: &[u8], channels: u8, expected: &[Pixel]) {
+    let image = ImageData::read_png_file(png).unwrap();
+    assert_eq!(image.channels(), channels);
+    let image = ImageData::decode_slice(&image.to_qoi_bytes()).unwrap();
+    assert_eq!(image.channels(), channels);
+    assert_eq!(image.pixels().collect::<Vec<_>>(), expected);
+}
+
+fn gray(v: u8) -> Pixel {
+    Pixel::rgb(v, v, v)
+}
+
+#[test]
+fn grayscale_at_every_bit_depth() {
+    use png::{BitDepth::*, ColorType::Grayscale};
+    let expected = [gray(0), gray(255), gray(255), gray(0)];
+    // One row of four pixels, packed most significant bits first.
+    assert_reads_as(
+        &png((4, 1), Grayscale, One, None, &[0b0110_0000]),
+        3,
+        &expected,
+    );
+    assert_reads_as(
+        &png((4, 1), Grayscale, Two, None, &[0b0011_1100]),
+        3,
+        &expected,
+    );
+    assert_reads_as(
+        &png((4, 1), Grayscale, Four, None, &[0x0f, 0xf0]),
+        3,
+        &expected,
+    );
+    assert_reads_as(
+        &png((4, 1), Grayscale, Eight, None, &[0, 255, 255, 0]),
+        3,
+        &expected,
+    );
+    let sixteen = [0x00, 0x00, 0xff, 0xff, 0xff, 0x00, 0x00, 0xff];
+    assert_reads_as(
+        &png((4, 1), Grayscale, Sixteen, None, &sixteen),
+        3,
+        &expected,
+    );
+
+    let ramp: Vec<u8> = (0..=255).collect();
+    let expected: Vec<Pixel> = ramp.iter().map(|&v| gray(v)).collect();
+    assert_reads_as(&png((16, 16), Grayscale, Eight, None, &ramp), 3, &expected);
+}
+
+#[test]
+fn grayscale_with_alpha() {
+    use png::{BitDepth::*, ColorType::*};
+    let data = [10, 255, 20, 128, 30, 0];
+    let expected = [
+        Pixel::rgba(10, 10, 10, 255),
+        Pixel::rgba(20, 20, 20, 128),
+        Pixel::rgba(30, 30, 30, 0),
+    ];
+    assert_reads_as(
+        &png((3, 1), GrayscaleAlpha, Eight, None, &data),
+        4,
+        &expected,
+    );
+
+    // A tRNS chunk makes one gray level transparent.
+    let trns = [0, 20];
+    let png = png((3, 1), Grayscale, Eight, Some(&trns), &[10, 20, 30]);
+    let expected = [
+        Pixel::rgba(10, 10, 10, 255),
+        Pixel::rgba(20, 20, 20, 0),
+        Pixel::rgba(30, 30, 30, 255),
+    ];
+    assert_reads_as(&png, 4, &expected);
+}
+
+#[test]
+fn palette_images() {
+    use png::{BitDepth::*, ColorType::Indexed};
+    let palette = [
+        Pixel::rgb(255, 0, 0),
+        Pixel::rgb(0, 255, 0),
+        Pixel::rgb(0, 0, 255),
+    ];
+    let plte: Vec<u8> = palette.iter().flat_map(|p| [p.r, p.g, p.b]).collect();
+    let indices = [2, 0, 1, 1, 0, 2];
+    let expected: Vec<Pixel> = indices.iter().map(|&i| palette[i as usize]).collect();
+
+    let mut encoded = Vec::new();
+    let mut encoder = png::Encoder::new(&mut encoded, 3, 2);
+    encoder.set_color(Indexed);
+    encoder.set_depth(Eight);
+    encoder.set_palette(plte);
+    encoder
+        .write_header()
+        .unwrap()
+        .write_image_data(&indices)
+        .unwrap();
+    assert_reads_as(&encoded, 3, &expected);
+
+    // Palettes with translucent entries come out with an alpha channel.
+    let translucent = [palette[0], Pixel::rgba(0, 255, 0, 100), palette[2]];
+    let image = ImageData::from_rgba(3, 2, 4, 0, vec![0; 24]).unwrap();
+    let mut encoded = Vec::new();
+    image
+        .write_png_indexed(&translucent, &indices, &mut encoded)
+        .unwrap();
+    let expected: Vec<Pixel> = indices.iter().map(|&i| translucent[i as usize]).collect();
+    assert_reads_as(&encoded, 4, &expected);
+}
+
+#[test]
+fn sixteen_bit_rgb_and_rgba() {
+    use png::{BitDepth::Sixteen, ColorType::*};
+    let rgb = [0x12, 0xff, 0x34, 0x00, 0x56, 0x80];
+    assert_reads_as(
+        &png((1, 1), Rgb, Sixteen, None, &rgb),
+        3,
+        &[Pixel::rgb(0x12, 0x34, 0x56)],
+    );
+    let rgba = [0x12, 0xff, 0x34, 0x00, 0x56, 0x80, 0x78, 0x01];
+    let expected = [Pixel::rgba(0x12, 0x34, 0x56, 0x78)];
+    assert_reads_as(&png((1, 1), Rgba, Sixteen, None, &rgba), 4, &expected);
+}
@@ -0,0 +1,68 @@
+mod common;
+
+use qoi_decoder::ImageData;
+
+use common::{has_chunk, load};
+
+/// The color type byte of the PNG's IHDR chunk: 2 for RGB, 6 for RGBA.
+fn png_color_type(png: &[u8]) -> u8 {
+    assert_eq!(&png[12..16], b"IHDR");
+    png[25]
+}
+
+#[test]
+fn color_type_follows_channels() {
+    for (name, color_type) in [("kodim23.qoi", 2), ("dice.qoi", 6)] {
+        let image = load(name).crop(0, 0, 128, 128).unwrap();
+        let mut png = Vec::new();
+        image.write_png_file(&mut png).unwrap();
+        assert_eq!(png_color_type(&png), color_type, "{}", name);
+
+        let decoded = ImageData::read_png_file(png.as_slice()).unwrap();
+        assert_eq!(decoded.channels(), image.channels(), "{}", name);
+        assert_eq!(decoded.dimensions(), image.dimensions(), "{}", name);
+        assert!(decoded.as_rgba_bytes() == image.as_rgba_bytes(), "{}", name);
+    }
+}
+
+#[test]
+fn rgb_png_is_smaller_than_rgba() {
+    let image = load("kodim23.qoi").crop(0, 0, 128, 128).unwrap();
+    let mut rgb = Vec::new();
+    image.write_png_file(&mut rgb).unwrap();
+    let as_rgba = ImageData::from_rgba(
+        image.width(),
+        image.height(),
+        4,
+        image.colorspace(),
+        image.as_rgba_bytes().to_vec(),
+    )
+    .unwrap();
+    let mut rgba = Vec::new();
+    as_rgba.write_png_file(&mut rgba).unwrap();
+    assert!(rgb.len() < rgba.len());
+}
+
+#[test]
+fn colorspace_picks_the_srgb_or_gamma_chunk() {
+    let image = load("kodim23.qoi").crop(0, 0, 16, 16).unwrap();
+    for colorspace in [0, 1] {
+        let image = ImageData::from_rgba(
+            image.width(),
+            image.height(),
+            image.channels(),
+            colorspace,
+            image.as_rgba_bytes().to_vec(),
+        )
+        .unwrap();
+        let mut png = Vec::new();
+        image.write_png_file(&mut png).unwrap();
+        // sRGB gets its own chunk; linear is a gamma of 1 with no sRGB chunk to override it.
+        assert_eq!(has_chunk(&png, b"sRGB"), colorspace == 0);
+        if colorspace == 1 {
+            assert!(has_chunk(&png, b"gAMA"));
+        }
+        let decoded = ImageData::read_png_file(png.as_slice()).unwrap();
+        assert_eq!(decoded.colorspace(), colorspace);
+    }
+}
@@ -0,0 +1,52 @@
+mod common;
+
+use qoi_decoder::ImageData;
+
+use common::load;
+
+/// Splits a binary Netpbm file written by `write_ppm` into its header and samples.
+fn split_header(bytes: &[u8]) -> (&str, &[u8]) {
+    let header_lines = if bytes.starts_with(b"P6") { 3 } else { 7 };
+    let mut end = 0;
+    for _ in 0..header_lines {
+        end += bytes[end..].iter().position(|&b| b == b'\n').unwrap() + 1;
+    }
+    (std::str::from_utf8(&bytes[..end]).unwrap(), &bytes[end..])
+}
+
+#[test]
+fn three_channels_write_p6() {
+    let image = load("kodim23.qoi");
+    let mut ppm = Vec::new();
+    image.write_ppm(&mut ppm).unwrap();
+    let (header, samples) = split_header(&ppm);
+    assert_eq!(header, "P6\n768 512\n255\n");
+    let rgb: Vec<u8> = image
+        .as_rgba_bytes()
+        .chunks_exact(4)
+        .flat_map(|pixel| &pixel[..3])
+        .copied()
+        .collect();
+    assert!(samples == rgb);
+}
+
+#[test]
+fn four_channels_write_p7_with_alpha() {
+    let image = load("dice.qoi");
+    let mut pam = Vec::new();
+    image.write_ppm(&mut pam).unwrap();
+    let (header, samples) = split_header(&pam);
+    assert_eq!(
+        header,
+        "P7\nWIDTH 800\nHEIGHT 600\nDEPTH 4\nMAXVAL 255\nTUPLTYPE RGB_ALPHA\nENDHDR\n"
+    );
+    assert!(samples == image.as_rgba_bytes());
+}
+
+#[test]
+fn tiny_image_bytes() {
+    let image = ImageData::from_rgba(2, 1, 3, 0, vec![1, 2, 3, 255, 4, 5, 6, 255]).unwrap();
+    let mut ppm = Vec::new();
+    image.write_ppm(&mut ppm).unwrap();
+    assert_eq!(ppm, b"P6\n2 1\n255\n\x01\x02\x03\x04\x05\x06");
+}
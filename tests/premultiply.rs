@@ -0,0 +1,55 @@
+use qoi_decoder::{ImageData, ImageDataBuilder, Pixel};
+
+fn row(channels: u8, pixels: &[Pixel]) -> ImageData {
+    let image_data = pixels.iter().flat_map(|&p| <[u8; 4]>::from(p)).collect();
+    ImageDataBuilder::new(pixels.len() as u32, 1)
+        .channels(channels)
+        .pixels(image_data)
+        .build()
+        .unwrap()
+}
+
+fn pixels(image: &ImageData) -> Vec<Pixel> {
+    image.pixels().collect()
+}
+
+#[test]
+fn scales_by_alpha_and_back() {
+    let mut image = row(
+        4,
+        &[
+            Pixel::rgba(200, 100, 50, 0),
+            Pixel::rgba(200, 100, 50, 128),
+            Pixel::rgba(200, 100, 50, 255),
+        ],
+    );
+    image.premultiply_alpha();
+    assert_eq!(
+        pixels(&image),
+        [
+            Pixel::rgba(0, 0, 0, 0),
+            Pixel::rgba(100, 50, 25, 128),
+            Pixel::rgba(200, 100, 50, 255),
+        ]
+    );
+    image.unpremultiply_alpha();
+    // Color under zero alpha is gone for good; half alpha loses a little precision.
+    assert_eq!(
+        pixels(&image),
+        [
+            Pixel::rgba(0, 0, 0, 0),
+            Pixel::rgba(199, 100, 50, 128),
+            Pixel::rgba(200, 100, 50, 255),
+        ]
+    );
+}
+
+#[test]
+fn three_channel_images_are_untouched() {
+    let original = [Pixel::rgb(200, 100, 50), Pixel::rgb(1, 2, 3)];
+    let mut image = row(3, &original);
+    image.premultiply_alpha();
+    assert_eq!(pixels(&image), original);
+    image.unpremultiply_alpha();
+    assert_eq!(pixels(&image), original);
+}
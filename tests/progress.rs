@@ -0,0 +1,31 @@
+mod common;
+
+use qoi_decoder::{ImageData, ImageDataBuilder, Pixel};
+
+use common::read;
+
+fn progress_calls(bytes: &[u8]) -> Vec<(u64, u64)> {
+    let mut calls = Vec::new();
+    ImageData::decode_with_progress(bytes, |decoded, total| calls.push((decoded, total))).unwrap();
+    calls
+}
+
+#[test]
+fn small_image_reports_only_completion() {
+    assert_eq!(progress_calls(&read("dice.qoi")), [(480_000, 480_000)]);
+}
+
+#[test]
+fn large_image_reports_along_the_way() {
+    let total = 2000 * 1500;
+    let bytes = ImageDataBuilder::new(2000, 1500)
+        .fill(Pixel::rgb(1, 2, 3))
+        .build()
+        .unwrap()
+        .to_qoi_bytes();
+    let calls = progress_calls(&bytes);
+    assert!(calls.len() >= 3, "{:?}", calls);
+    assert!(calls.iter().all(|&(_, t)| t == total));
+    assert!(calls.windows(2).all(|pair| pair[0].0 < pair[1].0));
+    assert_eq!(calls.last(), Some(&(total, total)));
+}
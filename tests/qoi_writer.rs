@@ -0,0 +1,115 @@
+mod common;
+
+use qoi_decoder::{
+    Colorspace, EncodeOptions, ImageData, ImageDataBuilder, Pixel, QOIHeader, QoiError, QoiWriter,
+};
+
+use common::load;
+
+fn stream_encode(image: &ImageData) -> Vec<u8> {
+    let (width, height) = image.dimensions();
+    let mut writer = QoiWriter::new(
+        Vec::new(),
+        width,
+        height,
+        image.channels(),
+        image.colorspace(),
+    )
+    .unwrap();
+    for row in image.rows() {
+        writer.write_rgba(row).unwrap();
+    }
+    writer.finish().unwrap()
+}
+
+#[test]
+fn matches_the_in_memory_encoder() {
+    for name in [
+        "dice.qoi",
+        "kodim10.qoi",
+        "qoi_logo.qoi",
+        "testcard_rgba.qoi",
+    ] {
+        let image = load(name);
+        assert!(stream_encode(&image) == image.to_qoi_bytes(), "{}", name);
+    }
+}
+
+#[test]
+fn flushes_a_run_at_the_end_and_across_rows() {
+    let red = Pixel::rgb(255, 0, 0);
+    // Solid rows: every run but the first starts partway through the previous row.
+    let image = ImageDataBuilder::new(50, 7).fill(red).build().unwrap();
+    let bytes = stream_encode(&image);
+    assert_eq!(bytes, image.to_qoi_bytes());
+    assert_eq!(ImageData::decode_slice(&bytes).unwrap(), image);
+
+    // Ending in a short run must still emit it before the end marker.
+    let mut pixels = vec![0; 10 * 4];
+    pixels[..4].copy_from_slice(&[1, 2, 3, 255]);
+    let image = ImageDataBuilder::new(10, 1).pixels(pixels).build().unwrap();
+    assert_eq!(
+        ImageData::decode_slice(&stream_encode(&image)).unwrap(),
+        image
+    );
+}
+
+#[test]
+fn honors_encode_options() {
+    let image = load("testcard.qoi");
+    let options = EncodeOptions::default()
+        .use_index(false)
+        .max_run(8)
+        .colorspace(Some(Colorspace::Linear));
+    let (width, height) = image.dimensions();
+    let header = QOIHeader::new(width, height, image.channels(), 0);
+    let mut writer = QoiWriter::new_with(Vec::new(), header, options).unwrap();
+    for pixel in image.pixels() {
+        writer.write_pixel(pixel).unwrap();
+    }
+    assert!(writer.finish().unwrap() == image.to_qoi_bytes_with(options));
+}
+
+#[test]
+fn pixel_count_must_match_the_header() {
+    let mut writer = QoiWriter::new(Vec::new(), 2, 2, 4, 0).unwrap();
+    writer.write_rgba(&[0; 3 * 4]).unwrap();
+    assert!(matches!(
+        writer.finish(),
+        Err(QoiError::PixelCountMismatch {
+            expected: 4,
+            got: 3
+        })
+    ));
+
+    let mut writer = QoiWriter::new(Vec::new(), 2, 2, 4, 0).unwrap();
+    writer.write_rgba(&[0; 4 * 4]).unwrap();
+    assert!(matches!(
+        writer.write_pixel(Pixel::rgb(0, 0, 0)),
+        Err(QoiError::TooManyPixels)
+    ));
+
+    assert!(matches!(
+        QoiWriter::new(Vec::new(), 2, 2, 5, 0),
+        Err(QoiError::InvalidChannels(5))
+    ));
+}
+
+#[test]
+fn partial_pixels_are_rejected() {
+    let mut writer = QoiWriter::new(Vec::new(), 2, 1, 4, 0).unwrap();
+    assert!(matches!(
+        writer.write_rgba(&[1, 2, 3, 255, 4, 5]),
+        Err(QoiError::BufferSizeMismatch {
+            expected: 4,
+            actual: 6
+        })
+    ));
+    // Nothing from the rejected slice was encoded.
+    writer.write_rgba(&[0; 2 * 4]).unwrap();
+    let bytes = writer.finish().unwrap();
+    assert_eq!(
+        ImageData::decode_slice(&bytes).unwrap().as_rgba_bytes(),
+        [0, 0, 0, 0, 0, 0, 0, 0]
+    );
+}
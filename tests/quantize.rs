@@ -0,0 +1,52 @@
+mod common;
+
+use qoi_decoder::{ImageDataBuilder, Pixel};
+
+use common::load;
+
+#[test]
+fn two_color_image_gives_two_entry_palette() {
+    let (red, blue) = (Pixel::rgb(255, 0, 0), Pixel::rgb(0, 0, 255));
+    let pixels = (0..64)
+        .flat_map(|i| <[u8; 4]>::from(if i % 3 == 0 { red } else { blue }))
+        .collect();
+    let image = ImageDataBuilder::new(8, 8).pixels(pixels).build().unwrap();
+    let (palette, indices) = image.quantize(16);
+    assert_eq!(palette.len(), 2);
+    assert_eq!(indices.len(), 64);
+    for (pixel, index) in image.pixels().zip(indices) {
+        assert_eq!(palette[index as usize], pixel);
+    }
+}
+
+#[test]
+fn photo_respects_max_colors() {
+    let image = load("kodim23.qoi");
+    for max_colors in [1, 2, 16, 256] {
+        let (palette, indices) = image.quantize(max_colors);
+        assert_eq!(palette.len(), max_colors);
+        assert_eq!(indices.len(), image.pixels().count());
+        assert!(indices
+            .iter()
+            .all(|&index| (index as usize) < palette.len()));
+    }
+}
+
+#[test]
+fn more_colors_means_less_error() {
+    let image = load("kodim23.qoi");
+    let error = |max_colors| {
+        let (palette, indices) = image.quantize(max_colors);
+        image
+            .pixels()
+            .zip(indices)
+            .map(|(pixel, index)| {
+                let entry = palette[index as usize];
+                let d = |a: u8, b: u8| (a as i64 - b as i64).pow(2);
+                d(pixel.r, entry.r) + d(pixel.g, entry.g) + d(pixel.b, entry.b)
+            })
+            .sum::<i64>()
+    };
+    assert!(error(256) < error(16));
+    assert!(error(16) < error(2));
+}
@@ -0,0 +1,62 @@
+mod common;
+
+use qoi_decoder::{ImageData, Pixel};
+
+use common::load;
+
+#[test]
+fn nearest_upscale_duplicates_pixels() {
+    let image_data: Vec<u8> = (0..16).collect();
+    let image = ImageData::from_rgba(2, 2, 4, 0, image_data).unwrap();
+    let resized = image.resize_nearest(4, 4).unwrap();
+    assert_eq!(resized.dimensions(), (4, 4));
+    for (x, y, pixel) in resized.enumerate_pixels() {
+        assert_eq!(Some(pixel), image.get_pixel(x / 2, y / 2), "({}, {})", x, y);
+    }
+}
+
+#[test]
+fn nearest_to_zero_is_empty() {
+    let pixel: [u8; 4] = Pixel::rgb(1, 2, 3).into();
+    let image = ImageData::from_rgba(1, 1, 4, 0, pixel.to_vec()).unwrap();
+    let resized = image.resize_nearest(0, 5).unwrap();
+    assert!(resized.as_rgba_bytes().is_empty());
+}
+
+#[test]
+fn bilinear_downscale_interpolates_gradient() {
+    // A horizontal gradient where red increases by 10 per pixel.
+    let image_data: Vec<u8> = (0..8)
+        .flat_map(|x| [x * 10, 0, 0, 255])
+        .cycle()
+        .take(8 * 2 * 4)
+        .collect();
+    let image = ImageData::from_rgba(8, 2, 4, 0, image_data).unwrap();
+    let resized = image.resize_bilinear(4, 1).unwrap();
+    // Target pixel 1 is centered at source x = 2.5, halfway between 20 and 30.
+    let red: Vec<u8> = resized.pixels().map(|pixel| pixel.r).collect();
+    for (actual, expected) in red.iter().zip([5, 25, 45, 65]) {
+        assert!(actual.abs_diff(expected) <= 1, "{:?}", red);
+    }
+    assert!(resized.pixels().all(|pixel| pixel.a == 255));
+}
+
+#[test]
+fn bilinear_same_size_is_identity() {
+    let image = load("dice.qoi");
+    let (width, height) = image.dimensions();
+    let resized = image.resize_bilinear(width, height).unwrap();
+    assert!(resized.as_rgba_bytes() == image.as_rgba_bytes());
+}
+
+#[test]
+fn bilinear_handles_huge_dimensions() {
+    // Wide enough that a 16.16 source position times the width no longer fits in 64 bits.
+    let width = 12_000_000;
+    let image_data: Vec<u8> = (0..width)
+        .flat_map(|x| [x as u8, (x >> 8) as u8, (x >> 16) as u8, 255])
+        .collect();
+    let image = ImageData::from_rgba(width, 1, 4, 0, image_data).unwrap();
+    let resized = image.resize_bilinear(width, 1).unwrap();
+    assert!(resized.as_rgba_bytes() == image.as_rgba_bytes());
+}
@@ -0,0 +1,51 @@
+use qoi_decoder::{Endian, ImageData, ImageDataBuilder, Pixel};
+
+fn image_of(pixels: &[Pixel]) -> ImageData {
+    let bytes = pixels.iter().flat_map(|&p| <[u8; 4]>::from(p)).collect();
+    ImageDataBuilder::new(pixels.len() as u32, 1)
+        .pixels(bytes)
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn primaries_pack_into_their_own_bits() {
+    let image = image_of(&[
+        Pixel::rgb(255, 0, 0),
+        Pixel::rgb(0, 255, 0),
+        Pixel::rgb(0, 0, 255),
+        Pixel::rgb(255, 255, 255),
+        Pixel::rgba(0, 0, 0, 0),
+    ]);
+    let packed: Vec<u16> = image
+        .to_rgb565(Endian::Little)
+        .into_iter()
+        .map(u16::from_le)
+        .collect();
+    assert_eq!(packed, [0xf800, 0x07e0, 0x001f, 0xffff, 0x0000]);
+}
+
+#[test]
+fn low_bits_are_truncated() {
+    let image = image_of(&[Pixel::rgb(7, 3, 7), Pixel::rgb(8, 4, 8)]);
+    let packed: Vec<u16> = image
+        .to_rgb565(Endian::Little)
+        .into_iter()
+        .map(u16::from_le)
+        .collect();
+    assert_eq!(packed, [0x0000, 0x0821]);
+}
+
+#[test]
+fn byte_order_is_as_requested_in_memory() {
+    let image = image_of(&[Pixel::rgb(255, 0, 0), Pixel::rgb(0, 0, 255)]);
+    let bytes = |endian| -> Vec<u8> {
+        image
+            .to_rgb565(endian)
+            .iter()
+            .flat_map(|v| v.to_ne_bytes())
+            .collect()
+    };
+    assert_eq!(bytes(Endian::Little), [0x00, 0xf8, 0x1f, 0x00]);
+    assert_eq!(bytes(Endian::Big), [0xf8, 0x00, 0x00, 0x1f]);
+}
@@ -0,0 +1,44 @@
+mod common;
+
+use std::io::{self, Read};
+
+use qoi_decoder::{ImageData, RgbaReader};
+
+use common::read;
+
+#[test]
+fn copy_matches_full_decode() {
+    for name in ["dice.qoi", "kodim10.qoi", "qoi_logo.qoi"] {
+        let bytes = read(name);
+        let mut out = Vec::new();
+        io::copy(&mut RgbaReader::new(bytes.as_slice()).unwrap(), &mut out).unwrap();
+        let expected = ImageData::decode(bytes.as_slice()).unwrap();
+        assert!(out == expected.as_rgba_bytes(), "{}", name);
+    }
+}
+
+#[test]
+fn odd_sized_reads_split_pixels() {
+    let bytes = read("testcard_rgba.qoi");
+    let mut reader = RgbaReader::new(bytes.as_slice()).unwrap();
+    let mut out = Vec::new();
+    let mut buf = [0; 7];
+    loop {
+        let len = reader.read(&mut buf).unwrap();
+        if len == 0 {
+            break;
+        }
+        out.extend_from_slice(&buf[..len]);
+    }
+    assert!(out == ImageData::decode(bytes.as_slice()).unwrap().as_rgba_bytes());
+}
+
+#[test]
+fn errors_surface_after_the_good_pixels() {
+    let bytes = read("dice.qoi");
+    let truncated = &bytes[..bytes.len() / 2];
+    let mut out = Vec::new();
+    let result = RgbaReader::new(truncated).unwrap().read_to_end(&mut out);
+    assert_eq!(result.unwrap_err().kind(), io::ErrorKind::InvalidData);
+    assert!(!out.is_empty());
+}
@@ -0,0 +1,40 @@
+mod common;
+
+use qoi_decoder::{verify_roundtrip, QoiError, Roundtrip};
+
+use common::{qoi_stream, read};
+
+#[test]
+fn reference_files_round_trip() {
+    for name in [
+        "dice.qoi",
+        "kodim10.qoi",
+        "qoi_logo.qoi",
+        "testcard_rgba.qoi",
+    ] {
+        assert_eq!(
+            verify_roundtrip(&read(name)).unwrap(),
+            Roundtrip::Match,
+            "{}",
+            name
+        );
+    }
+}
+
+#[test]
+fn foreign_op_choices_still_match() {
+    // The same pixel three times as QOI_OP_RGBA, an index op and a run of one, where this
+    // crate's encoder would write one QOI_OP_RGBA and a run of two.
+    let bytes = qoi_stream(3, &[0xff, 1, 2, 3, 4, 0x0e, 0xc0]);
+    let result = verify_roundtrip(&bytes).unwrap();
+    assert!(result.is_match());
+}
+
+#[test]
+fn undecodable_input_is_an_error() {
+    let bytes = read("dice.qoi");
+    assert!(matches!(
+        verify_roundtrip(&bytes[..bytes.len() / 2]),
+        Err(QoiError::UnexpectedEof { .. })
+    ));
+}
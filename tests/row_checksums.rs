@@ -0,0 +1,71 @@
+mod common;
+
+use qoi_decoder::{row_checksums, ImageData, ImageDataBuilder};
+
+use common::load;
+
+const HEADER_LEN: usize = 14;
+
+/// A 16x16 image whose every pixel is too far from the last for anything but QOI_OP_RGB,
+/// and never repeats, so pixel `i` is the 4-byte op at `HEADER_LEN + 4 * i`.
+fn all_rgb_ops() -> ImageData {
+    let pixels = (1..=256_u32)
+        .flat_map(|i| [(i * 37) as u8, (i * 91) as u8, (i * 53) as u8, 255])
+        .collect();
+    ImageDataBuilder::new(16, 16)
+        .pixels(pixels)
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn checksums_match_the_encoded_file() {
+    for name in ["dice.qoi", "testcard_rgba.qoi", "qoi_logo.qoi"] {
+        let image = load(name);
+        let (bytes, checksums) = image.encode_with_row_checksums();
+        assert_eq!(bytes, image.to_qoi_bytes());
+        assert_eq!(checksums.len(), image.height() as usize);
+        assert_eq!(row_checksums(&bytes).unwrap(), checksums);
+    }
+}
+
+#[test]
+fn corrupting_one_byte_changes_only_its_rows_checksum() {
+    let image = all_rgb_ops();
+    let (bytes, checksums) = image.encode_with_row_checksums();
+    assert_eq!(bytes.len(), HEADER_LEN + 256 * 4 + 8);
+    for pixel in [0, 15, 16, 100, 255] {
+        for payload in 1..4 {
+            let mut corrupted = bytes.clone();
+            corrupted[HEADER_LEN + pixel * 4 + payload] ^= 0x10;
+            let changed: Vec<usize> = row_checksums(&corrupted)
+                .unwrap()
+                .iter()
+                .zip(&checksums)
+                .enumerate()
+                .filter(|(_, (a, b))| a != b)
+                .map(|(row, _)| row)
+                .collect();
+            assert_eq!(changed, [pixel / 16]);
+        }
+    }
+}
+
+#[test]
+fn rows_covered_by_an_earlier_run_have_no_ops() {
+    let mut pixels = all_rgb_ops().into_rgba_bytes();
+    // The last pixel of row 2 starts a stretch of one color through row 5, so the run that
+    // follows it starts in row 3 and covers rows 4 and 5 as well.
+    let color = [1, 2, 3, 255];
+    for pixel in 47..96 {
+        pixels[pixel * 4..pixel * 4 + 4].copy_from_slice(&color);
+    }
+    let image = ImageDataBuilder::new(16, 16)
+        .pixels(pixels)
+        .build()
+        .unwrap();
+    let (_, checksums) = image.encode_with_row_checksums();
+    assert_ne!(checksums[3], 0);
+    assert_eq!(checksums[4..6], [0, 0]);
+    assert_ne!(checksums[6], 0);
+}
@@ -0,0 +1,13 @@
+mod common;
+
+use common::load;
+
+#[test]
+fn rows_cover_the_image() {
+    let image = load("testcard.qoi");
+    let (width, height) = image.dimensions();
+    let rows: Vec<&[u8]> = image.rows().collect();
+    assert_eq!(rows.len(), height as usize);
+    assert!(rows.iter().all(|row| row.len() == width as usize * 4));
+    assert!(rows.concat() == image.as_rgba_bytes());
+}
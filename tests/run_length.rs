@@ -0,0 +1,55 @@
+mod common;
+
+use qoi_decoder::{ImageData, ImageDataBuilder, OpStats, Pixel, QoiDecoder, QoiError};
+
+use common::qoi_stream;
+
+#[test]
+fn largest_run_byte_is_62_pixels() {
+    let (image, stats) = ImageData::decode_slice_with_stats(&qoi_stream(62, &[0xfd])).unwrap();
+    assert!(image.pixels().all(|pixel| pixel == Pixel::rgb(0, 0, 0)));
+    assert_eq!((stats.run, stats.run_pixels), (1, 62));
+    assert!(ImageData::decode_slice(&qoi_stream(63, &[0xfd])).is_err());
+}
+
+#[test]
+fn bytes_above_the_largest_run_are_rgb_and_rgba() {
+    let image =
+        ImageData::decode_slice(&qoi_stream(64, &[0xfd, 0xfe, 1, 2, 3, 0xff, 4, 5, 6, 7])).unwrap();
+    let pixels: Vec<Pixel> = image.pixels().collect();
+    assert_eq!(pixels[61], Pixel::rgb(0, 0, 0));
+    assert_eq!(pixels[62], Pixel::rgb(1, 2, 3));
+    assert_eq!(pixels[63], Pixel::rgba(4, 5, 6, 7));
+}
+
+#[test]
+fn encoder_never_emits_runs_over_62() {
+    let image = ImageDataBuilder::new(1000, 1)
+        .fill(Pixel::rgb(0, 0, 0))
+        .build()
+        .unwrap();
+    let bytes = image.to_qoi_bytes();
+    let (decoded, stats): (ImageData, OpStats) =
+        ImageData::decode_slice_with_stats(&bytes).unwrap();
+    assert!(decoded.as_rgba_bytes() == image.as_rgba_bytes());
+    assert_eq!(stats.run, 1000_u64.div_ceil(62));
+    assert!(bytes[14..bytes.len() - 8].iter().all(|&op| op <= 0xfd));
+}
+
+#[test]
+fn run_past_the_last_pixel_is_an_error() {
+    // Four pixels declared, then a run of five: 0xc0 | (5 - 1).
+    let bytes = qoi_stream(4, &[0xc4]);
+    assert!(matches!(
+        ImageData::decode_slice(&bytes),
+        Err(QoiError::PixelCountMismatch {
+            expected: 4,
+            got: 5
+        })
+    ));
+    let pixels: Result<Vec<Pixel>, _> = QoiDecoder::new(bytes.as_slice()).unwrap().collect();
+    assert!(matches!(pixels, Err(QoiError::TooManyPixels)));
+    // A run that ends on the last pixel is fine.
+    let image = ImageData::decode_slice(&qoi_stream(4, &[0xc3])).unwrap();
+    assert_eq!(image.pixels().count(), 4);
+}
@@ -0,0 +1,48 @@
+mod common;
+
+use qoi_decoder::{ImageData, QoiError, QoiSequence};
+
+use common::load;
+
+#[test]
+fn decodes_every_frame_in_order() {
+    let first = load("dice.qoi");
+    let mut second = load("dice.qoi");
+    second.flip_vertical();
+    let mut third = load("dice.qoi");
+    third.flip_horizontal();
+    let frames = [first, second, third];
+    let stream: Vec<u8> = frames.iter().flat_map(ImageData::to_qoi_bytes).collect();
+
+    let decoded = QoiSequence::decode_all(stream.as_slice()).unwrap();
+    assert_eq!(decoded.len(), frames.len());
+    for (decoded, frame) in decoded.iter().zip(&frames) {
+        assert_eq!(decoded.dimensions(), frame.dimensions());
+        assert!(decoded.as_rgba_bytes() == frame.as_rgba_bytes());
+    }
+    assert!(QoiSequence::decode_all(&[][..]).unwrap().is_empty());
+}
+
+#[test]
+fn rejects_frames_of_different_sizes() {
+    let mut stream = load("dice.qoi").to_qoi_bytes();
+    stream.extend(load("testcard.qoi").to_qoi_bytes());
+    assert!(matches!(
+        QoiSequence::decode_all(stream.as_slice()),
+        Err(QoiError::DimensionMismatch {
+            expected: (800, 600),
+            actual: (256, 256)
+        })
+    ));
+}
+
+#[test]
+fn rejects_a_truncated_last_frame() {
+    let frame = load("dice.qoi").to_qoi_bytes();
+    let mut stream = frame.clone();
+    stream.extend_from_slice(&frame[..frame.len() - 1]);
+    assert!(matches!(
+        QoiSequence::decode_all(stream.as_slice()),
+        Err(QoiError::MissingEndMarker { .. })
+    ));
+}
@@ -0,0 +1,53 @@
+mod common;
+
+use qoi_decoder::{ImageData, OpStats};
+
+use common::{qoi_stream, read};
+
+#[test]
+fn counts_each_op_in_a_known_stream() {
+    // RGBA, RGB, DIFF, LUMA, INDEX back to the RGBA pixel, then a run of 3.
+    let bytes = qoi_stream(
+        8,
+        &[
+            0xff, 10, 20, 30, 40, 0xfe, 1, 2, 3, 0x7f, 0xa8, 0x88, 12, 0xc2,
+        ],
+    );
+    let (image, stats) = ImageData::decode_with_stats(bytes.as_slice()).unwrap();
+    assert_eq!(image.pixels().count(), 8);
+    assert_eq!(
+        stats,
+        OpStats {
+            rgb: 1,
+            rgba: 1,
+            index: 1,
+            diff: 1,
+            luma: 1,
+            run: 1,
+            run_pixels: 3,
+        }
+    );
+}
+
+#[test]
+fn every_pixel_is_accounted_for() {
+    for name in ["dice.qoi", "kodim23.qoi", "qoi_logo.qoi"] {
+        let bytes = read(name);
+        let (image, stats) = ImageData::decode_with_stats(bytes.as_slice()).unwrap();
+        let (width, height) = image.dimensions();
+        let single = stats.rgb + stats.rgba + stats.index + stats.diff + stats.luma;
+        assert_eq!(
+            single + stats.run_pixels,
+            width as u64 * height as u64,
+            "{}",
+            name
+        );
+        assert!(stats.run <= stats.run_pixels, "{}", name);
+        assert_eq!(
+            ImageData::decode_slice_with_stats(&bytes).unwrap().1,
+            stats,
+            "{}",
+            name
+        );
+    }
+}
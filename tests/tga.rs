@@ -0,0 +1,53 @@
+mod common;
+
+use qoi_decoder::ImageData;
+
+use common::load;
+
+/// Reads back the uncompressed true-color TGAs written by `write_tga`, honoring the origin
+/// bit, into RGBA8.
+fn read_tga(bytes: &[u8]) -> (u32, u32, Vec<u8>) {
+    assert_eq!(bytes[2], 2, "uncompressed true-color");
+    let width = u16::from_le_bytes([bytes[12], bytes[13]]) as usize;
+    let height = u16::from_le_bytes([bytes[14], bytes[15]]) as usize;
+    let bytes_per_pixel = bytes[16] as usize / 8;
+    let top_left = bytes[17] & 0x20 != 0;
+    let rows: Vec<&[u8]> = bytes[18 + bytes[0] as usize..]
+        .chunks_exact(width * bytes_per_pixel)
+        .collect();
+    assert_eq!(rows.len(), height);
+    let mut rgba = Vec::with_capacity(width * height * 4);
+    for y in 0..height {
+        let row = if top_left {
+            rows[y]
+        } else {
+            rows[height - 1 - y]
+        };
+        for pixel in row.chunks_exact(bytes_per_pixel) {
+            let a = if bytes_per_pixel == 4 { pixel[3] } else { 255 };
+            rgba.extend_from_slice(&[pixel[2], pixel[1], pixel[0], a]);
+        }
+    }
+    (width as u32, height as u32, rgba)
+}
+
+#[test]
+fn tga_round_trip() {
+    for name in ["dice", "testcard"] {
+        let image_data = load(&format!("{}.qoi", name));
+        let mut tga = Vec::new();
+        image_data.write_tga(&mut tga).unwrap();
+        let (width, height, rgba) = read_tga(&tga);
+        assert_eq!((width, height), image_data.dimensions());
+        assert!(rgba == image_data.as_rgba_bytes(), "{}", name);
+    }
+}
+
+#[test]
+fn tga_three_channels() {
+    let image_data = ImageData::from_rgba(2, 1, 3, 0, vec![1, 2, 3, 255, 4, 5, 6, 255]).unwrap();
+    let mut tga = Vec::new();
+    image_data.write_tga(&mut tga).unwrap();
+    assert_eq!(tga[16], 24);
+    assert_eq!(&tga[18..], [3, 2, 1, 6, 5, 4]);
+}
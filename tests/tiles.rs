@@ -0,0 +1,51 @@
+use qoi_decoder::{ImageData, ImageDataBuilder};
+
+fn numbered(width: u32, height: u32) -> ImageData {
+    let pixels = (0..width * height)
+        .flat_map(|i| [i as u8, (i >> 8) as u8, 0, 255])
+        .collect();
+    ImageDataBuilder::new(width, height)
+        .pixels(pixels)
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn ten_by_ten_into_four_by_four() {
+    let image = numbered(10, 10);
+    let tiles: Vec<ImageData> = image.tiles(4, 4).collect();
+    assert_eq!(tiles.len(), 9);
+    let sizes: Vec<(u32, u32)> = tiles.iter().map(|tile| tile.dimensions()).collect();
+    assert_eq!(
+        sizes,
+        [
+            (4, 4),
+            (4, 4),
+            (2, 4),
+            (4, 4),
+            (4, 4),
+            (2, 4),
+            (4, 2),
+            (4, 2),
+            (2, 2),
+        ]
+    );
+    for (i, tile) in tiles.iter().enumerate() {
+        let (x, y) = (i as u32 % 3 * 4, i as u32 / 3 * 4);
+        let (w, h) = tile.dimensions();
+        let expected = image.crop(x, y, w, h).unwrap();
+        assert!(
+            tile.as_rgba_bytes() == expected.as_rgba_bytes(),
+            "tile {}",
+            i
+        );
+    }
+}
+
+#[test]
+fn zero_tile_size_yields_nothing() {
+    let image = numbered(3, 3);
+    assert_eq!(image.tiles(0, 2).count(), 0);
+    assert_eq!(image.tiles(2, 0).count(), 0);
+    assert_eq!(image.tiles(5, 5).count(), 1);
+}
@@ -0,0 +1,99 @@
+use qoi_decoder::{ImageData, ImageDataBuilder, QoiError};
+
+/// A four-channel image whose pixel at `(x, y)` is `rgb(x, y, 0)`.
+fn gradient(width: u32, height: u32) -> ImageData {
+    let pixels = (0..height)
+        .flat_map(|y| (0..width).flat_map(move |x| [x as u8, y as u8, 0, 255]))
+        .collect();
+    ImageDataBuilder::new(width, height)
+        .pixels(pixels)
+        .build()
+        .unwrap()
+}
+
+fn position(image: &ImageData, x: u32, y: u32) -> (u8, u8) {
+    let pixel = image.get_pixel(x, y).unwrap();
+    (pixel.r, pixel.g)
+}
+
+#[test]
+fn crop_keeps_the_requested_corners() {
+    let image = gradient(6, 4);
+    let cropped = image.crop(2, 1, 3, 2).unwrap();
+    assert_eq!(cropped.dimensions(), (3, 2));
+    assert_eq!(position(&cropped, 0, 0), (2, 1));
+    assert_eq!(position(&cropped, 2, 0), (4, 1));
+    assert_eq!(position(&cropped, 0, 1), (2, 2));
+    assert_eq!(position(&cropped, 2, 1), (4, 2));
+    assert!(image.crop(0, 0, 6, 4).unwrap().as_rgba_bytes() == image.as_rgba_bytes());
+    assert_eq!(image.crop(6, 4, 0, 0).unwrap().dimensions(), (0, 0));
+}
+
+#[test]
+fn crop_outside_the_image_fails() {
+    let image = gradient(6, 4);
+    for (x, y, width, height) in [
+        (5, 0, 2, 1),
+        (0, 3, 1, 2),
+        (7, 0, 0, 0),
+        (u32::MAX, 0, 2, 1),
+    ] {
+        assert!(
+            matches!(
+                image.crop(x, y, width, height),
+                Err(QoiError::RegionOutOfBounds { .. })
+            ),
+            "{} {} {} {}",
+            x,
+            y,
+            width,
+            height
+        );
+    }
+}
+
+#[test]
+fn flips_mirror_the_image_and_undo_themselves() {
+    for (width, height) in [(5, 3), (4, 4), (1, 6), (0, 2)] {
+        let image = gradient(width, height);
+        let mut vertical = gradient(width, height);
+        vertical.flip_vertical();
+        let mut horizontal = gradient(width, height);
+        horizontal.flip_horizontal();
+        for (x, y, _) in image.enumerate_pixels() {
+            assert_eq!(position(&vertical, x, y), (x as u8, (height - 1 - y) as u8));
+            assert_eq!(
+                position(&horizontal, x, y),
+                ((width - 1 - x) as u8, y as u8)
+            );
+        }
+        vertical.flip_vertical();
+        horizontal.flip_horizontal();
+        assert!(vertical.as_rgba_bytes() == image.as_rgba_bytes());
+        assert!(horizontal.as_rgba_bytes() == image.as_rgba_bytes());
+    }
+}
+
+#[test]
+fn rotations_move_corners_and_compose() {
+    let image = gradient(5, 3);
+    let quarter = image.rotate90();
+    assert_eq!(quarter.dimensions(), (3, 5));
+    // Clockwise: the bottom-left corner comes up to the top-left.
+    assert_eq!(position(&quarter, 0, 0), (0, 2));
+    assert_eq!(position(&quarter, 2, 0), (0, 0));
+    assert_eq!(position(&quarter, 2, 4), (4, 0));
+    let half = image.rotate180();
+    assert_eq!(half.dimensions(), (5, 3));
+    assert_eq!(position(&half, 0, 0), (4, 2));
+    let three_quarters = image.rotate270();
+    assert_eq!(three_quarters.dimensions(), (3, 5));
+    assert_eq!(position(&three_quarters, 0, 0), (4, 0));
+
+    assert!(quarter.rotate90().as_rgba_bytes() == half.as_rgba_bytes());
+    assert!(half.rotate90().as_rgba_bytes() == three_quarters.as_rgba_bytes());
+    let full = three_quarters.rotate90();
+    assert_eq!(full.dimensions(), (5, 3));
+    assert!(full.as_rgba_bytes() == image.as_rgba_bytes());
+    assert!(quarter.rotate270().as_rgba_bytes() == image.as_rgba_bytes());
+}
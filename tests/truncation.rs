@@ -0,0 +1,110 @@
+//! Input cut partway through each kind of op, the header and the end marker. Every cut
+//! must be an error, not a panic, and point at the start of whatever got cut.
+
+mod common;
+
+use qoi_decoder::{ImageData, QoiDecoder, QoiError};
+
+use common::{qoi_stream, END_MARKER};
+
+const HEADER_LEN: usize = 14;
+
+/// A stream of `first` (which decodes one pixel) followed by `op` as the second pixel,
+/// with both ops whole.
+fn two_pixels(first: &[u8], op: &[u8]) -> Vec<u8> {
+    let ops: Vec<u8> = first.iter().chain(op).copied().collect();
+    qoi_stream(2, &ops)
+}
+
+fn assert_eof_at(bytes: &[u8], offset: usize, context: &str) {
+    let result = ImageData::decode_slice(bytes);
+    assert!(
+        matches!(result, Err(QoiError::UnexpectedEof { offset: o }) if o == offset),
+        "{}: {:?}",
+        context,
+        result.err()
+    );
+    let streamed = QoiDecoder::new(bytes).unwrap().find_map(Result::err);
+    assert!(
+        matches!(streamed, Some(QoiError::UnexpectedEof { offset: o }) if o == offset),
+        "{} (streamed): {:?}",
+        context,
+        streamed
+    );
+}
+
+#[test]
+fn every_op_cut_at_every_byte() {
+    let rgba = [0xff, 10, 20, 30, 40];
+    let cases: [(&str, &[u8]); 6] = [
+        ("RGB", &[0xfe, 1, 2, 3]),
+        ("RGBA", &[0xff, 1, 2, 3, 4]),
+        ("DIFF", &[0x40]),
+        ("LUMA", &[0x80, 0x88]),
+        // Slot 12 holds the first pixel, (10, 20, 30, 40).
+        ("INDEX", &[12]),
+        ("RUN", &[0xc0]),
+    ];
+    for (name, op) in cases {
+        let bytes = two_pixels(&rgba, op);
+        let op_start = HEADER_LEN + rgba.len();
+        assert!(
+            ImageData::decode_slice(&bytes).is_ok(),
+            "{} should decode whole",
+            name
+        );
+        for cut in op_start..op_start + op.len() {
+            assert_eof_at(&bytes[..cut], op_start, &format!("{} cut at {}", name, cut));
+        }
+    }
+}
+
+#[test]
+fn first_op_cut() {
+    let bytes = qoi_stream(1, &[0xff, 1, 2, 3, 4]);
+    for cut in HEADER_LEN..HEADER_LEN + 5 {
+        assert_eof_at(&bytes[..cut], HEADER_LEN, &format!("cut at {}", cut));
+    }
+}
+
+#[test]
+fn header_cut() {
+    let bytes = qoi_stream(1, &[0xfe, 1, 2, 3]);
+    for cut in 4..HEADER_LEN {
+        let result = ImageData::decode_slice(&bytes[..cut]);
+        assert!(
+            matches!(result, Err(QoiError::UnexpectedEof { offset: 0 })),
+            "cut at {}: {:?}",
+            cut,
+            result.err()
+        );
+        assert!(matches!(
+            QoiDecoder::new(&bytes[..cut]),
+            Err(QoiError::UnexpectedEof { offset: 0 })
+        ));
+    }
+}
+
+#[test]
+fn end_marker_cut() {
+    let bytes = qoi_stream(1, &[0xfe, 1, 2, 3]);
+    let marker_at = bytes.len() - END_MARKER.len();
+    for cut in marker_at..bytes.len() {
+        let result = ImageData::decode_slice(&bytes[..cut]);
+        assert!(
+            matches!(result, Err(QoiError::MissingEndMarker { offset }) if offset == marker_at),
+            "cut at {}: {:?}",
+            cut,
+            result.err()
+        );
+        let streamed = QoiDecoder::new(&bytes[..cut])
+            .unwrap()
+            .find_map(Result::err);
+        assert!(
+            matches!(streamed, Some(QoiError::MissingEndMarker { offset }) if offset == marker_at),
+            "cut at {} (streamed): {:?}",
+            cut,
+            streamed
+        );
+    }
+}
@@ -0,0 +1,44 @@
+mod common;
+
+use qoi_decoder::{EncodeOptions, ImageData};
+
+use common::read;
+
+#[test]
+fn trusted_decode_matches_checked_decode() {
+    for name in [
+        "dice.qoi",
+        "kodim10.qoi",
+        "qoi_logo.qoi",
+        "testcard.qoi",
+        "testcard_rgba.qoi",
+        "wikipedia_008.qoi",
+    ] {
+        let bytes = read(name);
+        let checked = ImageData::decode_slice(&bytes).unwrap();
+        // SAFETY: `decode_slice` just accepted the same bytes.
+        let trusted = unsafe { ImageData::decode_trusted(&bytes) };
+        assert_eq!(trusted.diff(&checked), None, "{}", name);
+    }
+}
+
+#[test]
+fn trusted_decode_handles_every_op() {
+    let image = ImageData::decode_slice(&read("testcard_rgba.qoi")).unwrap();
+    for options in [
+        EncodeOptions::default(),
+        EncodeOptions::default().force_rgba(true),
+        EncodeOptions::default().use_index(false).max_run(1),
+    ] {
+        let bytes = image.to_qoi_bytes_with(options);
+        // SAFETY: the bytes come straight from the encoder.
+        let trusted = unsafe { ImageData::decode_trusted(&bytes) };
+        assert_eq!(trusted, image);
+    }
+    let empty = ImageData::from_rgba(0, 0, 4, 0, Vec::new()).unwrap();
+    // SAFETY: as above.
+    assert_eq!(
+        unsafe { ImageData::decode_trusted(&empty.to_qoi_bytes()) },
+        empty
+    );
+}
@@ -0,0 +1,73 @@
+mod common;
+
+use qoi_decoder::{DecodeOptions, DecodeWarning, ImageData};
+
+use common::{read, END_MARKER};
+
+fn warnings(bytes: &[u8]) -> Vec<DecodeWarning> {
+    ImageData::decode_slice_with_warnings(bytes).unwrap().1
+}
+
+#[test]
+fn clean_file_has_no_warnings() {
+    assert_eq!(warnings(&read("dice.qoi")), []);
+}
+
+#[test]
+fn trailing_bytes() {
+    let mut bytes = read("dice.qoi");
+    bytes.extend_from_slice(b"extra");
+    assert_eq!(warnings(&bytes), [DecodeWarning::TrailingBytes(5)]);
+}
+
+#[test]
+fn missing_end_marker() {
+    let bytes = read("dice.qoi");
+    let (image, warnings) =
+        ImageData::decode_slice_with_warnings(&bytes[..bytes.len() - 8]).unwrap();
+    assert_eq!(warnings, [DecodeWarning::MissingEndMarker]);
+    assert!(image.as_rgba_bytes() == ImageData::decode_slice(&bytes).unwrap().as_rgba_bytes());
+}
+
+#[test]
+fn truncated_pixels() {
+    let bytes = read("dice.qoi");
+    let (width, height) = ImageData::decode_slice(&bytes).unwrap().dimensions();
+    let warnings = warnings(&bytes[..bytes.len() / 2]);
+    match warnings[..] {
+        [DecodeWarning::PixelCountMismatch { expected, got }, DecodeWarning::MissingEndMarker] => {
+            assert_eq!(expected, width as u64 * height as u64);
+            assert!(got > 0 && got < expected);
+        }
+        _ => panic!("unexpected warnings: {:?}", warnings),
+    }
+}
+
+#[test]
+fn run_past_the_declared_pixels() {
+    let mut bytes = b"qoif".to_vec();
+    bytes.extend_from_slice(&3_u32.to_be_bytes());
+    bytes.extend_from_slice(&1_u32.to_be_bytes());
+    bytes.extend_from_slice(&[4, 0]);
+    bytes.push(0xc0 | 4);
+    bytes.extend_from_slice(&END_MARKER);
+    let (image, warnings) = ImageData::decode_slice_with_warnings(&bytes).unwrap();
+    assert_eq!(
+        warnings,
+        [DecodeWarning::PixelCountMismatch {
+            expected: 3,
+            got: 5
+        }]
+    );
+    assert_eq!(image.as_rgba_bytes().len(), 12);
+}
+
+#[test]
+fn no_marker_warnings_when_the_marker_is_not_required() {
+    let mut bytes = read("dice.qoi");
+    bytes.truncate(bytes.len() - 8);
+    bytes.extend_from_slice(b"container data");
+    let options = DecodeOptions::default().require_end_marker(false);
+    let (_, warnings) = ImageData::decode_slice_with_warnings_with(&bytes, options).unwrap();
+    assert_eq!(warnings, []);
+}